@@ -0,0 +1,74 @@
+//! Benchmarks painting a styled text through `paint`, `Display` and `write_to`.
+//!
+//! Run with `cargo bench --bench paint`.
+//!
+//! `legacy_paint` is the allocation heavy path `paint` used before `Display` was implemented,
+//! kept here to compare against.
+
+use std::fmt::Write as _;
+
+use criterion::{ black_box, criterion_group, criterion_main, Criterion };
+use term_tools::{
+    ansi_code::ANSIEscapeCode,
+    styled,
+    styles::{ basic_color, formatter, paint_type::PaintType, rgb::Rgb, Styles },
+};
+
+const TEXT: &str = "2024-05-01T12:00:00Z INFO request handled in 12ms";
+
+fn legacy_paint(styles: &[Styles], text: &str) -> String {
+    let mut default_paint_type = PaintType::FG;
+    let start_codes_list: Vec<String> = styles
+        .iter()
+        .rev()
+        .filter_map(|s| {
+            if let Styles::StylePaintType(p) = s {
+                default_paint_type = *p;
+                return None;
+            }
+            let t = s.make_styles(Some(&default_paint_type));
+            Some(ANSIEscapeCode::new(t.as_str()).code())
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    let start_codes = start_codes_list.join("");
+    let end_codes = ANSIEscapeCode::new(&formatter::RESET.make_styles(Some(&default_paint_type))).code();
+    format!("{}{}{}", start_codes, text, end_codes)
+}
+
+fn bench_paint(c: &mut Criterion) {
+    let mut txt = styled(TEXT);
+    txt.rgb(30, 144, 255).bold().underline().black().bg();
+    let styles = [
+        Styles::StyleRgb(Rgb { r: 30, g: 144, b: 255 }),
+        formatter::BOLD,
+        formatter::UNDERLINE,
+        basic_color::BLACK,
+        Styles::StylePaintType(PaintType::BG),
+    ];
+    assert_eq!(legacy_paint(&styles, TEXT), txt.paint());
+
+    c.bench_function("legacy paint", |b| b.iter(|| legacy_paint(black_box(&styles), black_box(TEXT))));
+    c.bench_function("paint", |b| b.iter(|| black_box(&mut txt).paint()));
+
+    let mut buffer = String::with_capacity(256);
+    c.bench_function("display into a reused buffer", |b| {
+        b.iter(|| {
+            buffer.clear();
+            write!(buffer, "{}", black_box(&txt)).unwrap();
+        })
+    });
+
+    let mut bytes: Vec<u8> = Vec::with_capacity(256);
+    c.bench_function("write_to a reused Vec", |b| {
+        b.iter(|| {
+            bytes.clear();
+            black_box(&txt).write_to(&mut bytes).unwrap();
+        })
+    });
+}
+
+criterion_group!(benches, bench_paint);
+criterion_main!(benches);
@@ -1,9 +1,18 @@
-/// A module for creating ansi escape code.
-///
-/// This module provides an struct `ANSIEscapeCode`
-/// that represents ansi escape code, with `parameter` field.
-/// It also implements the `ANSIEscapeCode`, with `new` and `code` method
-/// which allows for generating anis escape code and getting a code.
+//! A module for creating ansi escape code.
+//!
+//! This module provides an struct `ANSIEscapeCode`
+//! that represents ansi escape code, with `parameter` field.
+//! It also implements the `ANSIEscapeCode`, with `new` and `code` method
+//! which allows for generating anis escape code and getting a code.
+//! The `parser` module reads painted strings back into styled spans,
+//! the `hyperlink` module writes OSC 8 hyperlinks and the `control` module
+//! writes the sequences that move the cursor and erase the screen.
+
+// =======================================================================
+
+pub mod control;
+pub mod hyperlink;
+pub mod parser;
 
 // =======================================================================
 
@@ -0,0 +1,306 @@
+//! A module for the escape sequences that control the cursor and the screen.
+//!
+//! This module provides an enum `Command` for the CSI sequences that interactive programs use
+//! besides SGR: moving, saving and hiding the cursor, erasing parts of the line or the screen,
+//! setting the scroll region and switching to the alternate screen. The struct `CommandQueue`
+//! collects commands and text and writes them to any `io::Write` in one go.
+
+// =======================================================================
+
+use std::{ fmt, io };
+
+// =======================================================================
+
+/// An enum representing which part of the line or the screen is erased.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EraseMode {
+    /// From the cursor to the end, `0`.
+    #[default]
+    ToEnd,
+    /// From the start to the cursor, `1`.
+    ToStart,
+    /// Everything, `2`.
+    All,
+}
+
+impl EraseMode {
+    /// Returns the parameter of the mode.
+    pub const fn code(&self) -> u8 {
+        match self {
+            EraseMode::ToEnd => 0,
+            EraseMode::ToStart => 1,
+            EraseMode::All => 2,
+        }
+    }
+}
+
+/// An enum representing a command for the cursor or the screen.
+///
+/// Rows and columns start at 1 in the top left corner, like terminals count them.
+/// Moves by 0 cells write nothing, because terminals move by 1 cell for a `0` parameter.
+///
+/// # Example:
+/// ```
+/// use term_tools::ansi_code::control::{ Command, EraseMode };
+/// assert_eq!(Command::MoveTo { row: 3, column: 10 }.code(), "\x1b[3;10H");
+/// assert_eq!(Command::EraseLine(EraseMode::All).code(), "\x1b[2K");
+/// assert_eq!(Command::CursorUp(0).code(), "");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Command {
+    /// Moves the cursor up by the number of rows, `CSI n A`.
+    CursorUp(u16),
+    /// Moves the cursor down by the number of rows, `CSI n B`.
+    CursorDown(u16),
+    /// Moves the cursor right by the number of columns, `CSI n C`.
+    CursorRight(u16),
+    /// Moves the cursor left by the number of columns, `CSI n D`.
+    CursorLeft(u16),
+    /// Moves the cursor to the row and column, `CSI row;column H`.
+    MoveTo { row: u16, column: u16 },
+    /// Moves the cursor to the column of the current row, `CSI n G`.
+    MoveToColumn(u16),
+    /// Saves the position of the cursor, `ESC 7`.
+    ///
+    /// The DEC sequence is used instead of `CSI s`, which more terminals support.
+    SaveCursor,
+    /// Moves the cursor back to the saved position, `ESC 8`.
+    RestoreCursor,
+    /// Hides the cursor, `CSI ?25l`.
+    HideCursor,
+    /// Shows the cursor, `CSI ?25h`.
+    ShowCursor,
+    /// Erases a part of the line of the cursor, `CSI n K`.
+    EraseLine(EraseMode),
+    /// Erases a part of the screen, `CSI n J`.
+    EraseScreen(EraseMode),
+    /// Scrolls the content up by the number of lines, `CSI n S`.
+    ScrollUp(u16),
+    /// Scrolls the content down by the number of lines, `CSI n T`.
+    ScrollDown(u16),
+    /// Limits scrolling to the rows from `top` to `bottom`, `CSI top;bottom r`.
+    SetScrollRegion { top: u16, bottom: u16 },
+    /// Lets the whole screen scroll again, `CSI r`.
+    ResetScrollRegion,
+    /// Switches to the alternate screen, `CSI ?1049h`.
+    EnterAlternateScreen,
+    /// Switches back to the main screen, `CSI ?1049l`.
+    LeaveAlternateScreen,
+}
+
+impl Command {
+    /// Returns a String that represent the escape sequence of the command.
+    pub fn code(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for Command {
+    /// Writes the escape sequence of the command.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::CursorUp(0) |
+            Command::CursorDown(0) |
+            Command::CursorRight(0) |
+            Command::CursorLeft(0) |
+            Command::ScrollUp(0) |
+            Command::ScrollDown(0) => Ok(()),
+            Command::CursorUp(n) => write!(f, "\x1b[{}A", n),
+            Command::CursorDown(n) => write!(f, "\x1b[{}B", n),
+            Command::CursorRight(n) => write!(f, "\x1b[{}C", n),
+            Command::CursorLeft(n) => write!(f, "\x1b[{}D", n),
+            Command::MoveTo { row, column } => write!(f, "\x1b[{};{}H", row, column),
+            Command::MoveToColumn(n) => write!(f, "\x1b[{}G", n),
+            Command::SaveCursor => f.write_str("\x1b7"),
+            Command::RestoreCursor => f.write_str("\x1b8"),
+            Command::HideCursor => f.write_str("\x1b[?25l"),
+            Command::ShowCursor => f.write_str("\x1b[?25h"),
+            Command::EraseLine(mode) => write!(f, "\x1b[{}K", mode.code()),
+            Command::EraseScreen(mode) => write!(f, "\x1b[{}J", mode.code()),
+            Command::ScrollUp(n) => write!(f, "\x1b[{}S", n),
+            Command::ScrollDown(n) => write!(f, "\x1b[{}T", n),
+            Command::SetScrollRegion { top, bottom } => write!(f, "\x1b[{};{}r", top, bottom),
+            Command::ResetScrollRegion => f.write_str("\x1b[r"),
+            Command::EnterAlternateScreen => f.write_str("\x1b[?1049h"),
+            Command::LeaveAlternateScreen => f.write_str("\x1b[?1049l"),
+        }
+    }
+}
+
+/// A struct for collecting commands and text that are written together.
+///
+/// Writing everything at once keeps the terminal from showing half drawn screens.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ ansi_code::control::{ Command, CommandQueue, EraseMode }, styled };
+/// let mut queue = CommandQueue::new();
+/// queue
+///     .push(Command::HideCursor)
+///     .push(Command::MoveTo { row: 1, column: 1 })
+///     .push(Command::EraseLine(EraseMode::All))
+///     .text(&styled("Loading").bold().paint());
+/// let mut out = Vec::new();
+/// queue.flush(&mut out).unwrap();
+/// assert_eq!(out, b"\x1b[?25l\x1b[1;1H\x1b[2K\x1b[1mLoading\x1b[0m");
+/// assert!(queue.is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CommandQueue {
+    buffer: Vec<u8>,
+}
+
+impl CommandQueue {
+    /// Returns an empty queue.
+    pub fn new() -> Self {
+        CommandQueue::default()
+    }
+
+    /// Adds a command to the queue.
+    pub fn push(&mut self, command: Command) -> &mut Self {
+        self.buffer.extend_from_slice(command.code().as_bytes());
+        self
+    }
+
+    /// Adds text to the queue, for example a painted `StyledText`.
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.buffer.extend_from_slice(text.as_bytes());
+        self
+    }
+
+    /// Returns the bytes that are written on the next flush.
+    ///
+    /// After a failed flush this can end in the middle of a character,
+    /// because only the bytes the writer accepted are removed.
+    pub fn pending(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Returns `true` if nothing is queued.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Writes the queue into the writer, flushes the writer and empties the queue.
+    ///
+    /// The bytes the writer accepted are removed from the queue even if writing fails later,
+    /// so the next flush writes only the rest and nothing is written twice.
+    /// A writer that accepts no bytes fails with `io::ErrorKind::WriteZero`.
+    pub fn flush<W: io::Write>(&mut self, mut writer: W) -> io::Result<()> {
+        let mut written = 0;
+        let result = loop {
+            if written == self.buffer.len() {
+                break Ok(());
+            }
+            match writer.write(&self.buffer[written..]) {
+                Ok(0) => {
+                    break Err(io::Error::new(io::ErrorKind::WriteZero, "failed to write the queued commands"));
+                }
+                Ok(n) => {
+                    written += n;
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => {
+                    break Err(err);
+                }
+            }
+        };
+        self.buffer.drain(..written);
+        result?;
+        writer.flush()
+    }
+}
+
+impl Extend<Command> for CommandQueue {
+    fn extend<I: IntoIterator<Item = Command>>(&mut self, commands: I) {
+        for command in commands {
+            self.push(command);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_codes() {
+        assert_eq!(Command::CursorUp(2).code(), "\x1b[2A");
+        assert_eq!(Command::CursorDown(1).code(), "\x1b[1B");
+        assert_eq!(Command::CursorRight(10).code(), "\x1b[10C");
+        assert_eq!(Command::CursorLeft(3).code(), "\x1b[3D");
+        assert_eq!(Command::CursorLeft(0).code(), "");
+        assert_eq!(Command::MoveToColumn(5).code(), "\x1b[5G");
+        assert_eq!(Command::SaveCursor.code(), "\x1b7");
+        assert_eq!(Command::RestoreCursor.code(), "\x1b8");
+        assert_eq!(Command::HideCursor.code(), "\x1b[?25l");
+        assert_eq!(Command::ShowCursor.code(), "\x1b[?25h");
+    }
+
+    #[test]
+    fn screen_codes() {
+        assert_eq!(Command::EraseLine(EraseMode::ToEnd).code(), "\x1b[0K");
+        assert_eq!(Command::EraseScreen(EraseMode::ToStart).code(), "\x1b[1J");
+        assert_eq!(Command::EraseScreen(EraseMode::All).code(), "\x1b[2J");
+        assert_eq!(Command::ScrollUp(2).code(), "\x1b[2S");
+        assert_eq!(Command::ScrollDown(0).code(), "");
+        assert_eq!(Command::SetScrollRegion { top: 2, bottom: 20 }.code(), "\x1b[2;20r");
+        assert_eq!(Command::ResetScrollRegion.code(), "\x1b[r");
+        assert_eq!(Command::EnterAlternateScreen.code(), "\x1b[?1049h");
+        assert_eq!(Command::LeaveAlternateScreen.code(), "\x1b[?1049l");
+    }
+
+    #[test]
+    fn queue_keeps_what_was_not_written() {
+        // accepts `limit` bytes, then fails
+        struct Limited {
+            out: Vec<u8>,
+            limit: usize,
+            interrupted: bool,
+        }
+        impl io::Write for Limited {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                if !self.interrupted {
+                    self.interrupted = true;
+                    return Err(io::ErrorKind::Interrupted.into());
+                }
+                if self.limit == 0 {
+                    return Err(io::Error::other("closed"));
+                }
+                let n = buf.len().min(self.limit).min(2);
+                self.out.extend_from_slice(&buf[..n]);
+                self.limit -= n;
+                Ok(n)
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        let mut queue = CommandQueue::new();
+        queue.extend([Command::SaveCursor, Command::CursorUp(1)]);
+        queue.text("x").push(Command::RestoreCursor);
+        let mut writer = Limited { out: vec![], limit: 3, interrupted: false };
+        assert!(queue.flush(&mut writer).is_err());
+        assert_eq!(writer.out, b"\x1b7\x1b");
+        assert_eq!(queue.pending(), b"[1Ax\x1b8");
+        let mut out = Vec::new();
+        queue.flush(&mut out).unwrap();
+        assert_eq!(out, b"[1Ax\x1b8");
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn writing_zero_bytes_fails() {
+        let mut queue = CommandQueue::new();
+        queue.text("x");
+        let err = queue.flush(&mut [0u8; 0][..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+        assert_eq!(queue.pending(), b"x");
+    }
+
+    #[test]
+    fn parser_skips_commands() {
+        let painted = format!("{}a{}b", Command::MoveTo { row: 1, column: 2 }, Command::EraseLine(EraseMode::All));
+        assert_eq!(crate::strip_ansi(&painted), "ab");
+    }
+}
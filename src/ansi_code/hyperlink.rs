@@ -0,0 +1,131 @@
+//! A module for OSC 8 hyperlinks.
+//!
+//! This module provides a struct `Hyperlink` that writes the escape sequences which make text
+//! clickable on terminals like iTerm2, kitty, WezTerm, GNOME Terminal and Windows Terminal:
+//! `\x1b]8;id=ID;URL\x1b\` opens the link and `\x1b]8;;\x1b\` closes it.
+
+// =======================================================================
+
+use std::fmt;
+
+// =======================================================================
+
+/// The sequence that closes a hyperlink.
+pub const CLOSE: &str = "\x1b]8;;\x1b\\";
+
+/// A struct representing an OSC 8 hyperlink.
+///
+/// Terminals underline all the text of links with the same id and url together when
+/// the mouse is over one of them, so a link that is split over lines can keep one id.
+///
+/// # Example:
+/// ```
+/// use term_tools::ansi_code::hyperlink::Hyperlink;
+/// let link = Hyperlink::new("https://example.com/a b");
+/// assert_eq!(link.open(), "\x1b]8;;https://example.com/a%20b\x1b\\");
+/// assert_eq!(Hyperlink::new("https://x.y").id("docs").open(), "\x1b]8;id=docs;https://x.y\x1b\\");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hyperlink {
+    url: String,
+    encoded_url: String,
+    id: Option<String>,
+}
+
+impl Hyperlink {
+    /// Returns a link to the url without an id.
+    pub fn new(url: &str) -> Self {
+        let mut encoded_url = String::with_capacity(url.len());
+        let _ = write_percent_encoded(&mut encoded_url, url, b"");
+        Hyperlink { url: url.to_string(), encoded_url, id: None }
+    }
+
+    /// Returns the link with the given id.
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Returns the url as it was given.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the url percent encoded like in the sequence that opens the link,
+    /// so it can be written to a terminal without control characters.
+    pub fn encoded_url(&self) -> &str {
+        &self.encoded_url
+    }
+
+    /// Returns the sequence that opens the link.
+    ///
+    /// Terminals only accept the printable ASCII characters in the url,
+    /// so every other byte is percent encoded, like spaces, control characters and
+    /// the bytes of non ASCII characters. In the id `:`, `;` and `=` are encoded too,
+    /// because they separate the parameters.
+    pub fn open(&self) -> String {
+        let mut open = String::new();
+        let _ = self.write_open(&mut open);
+        open
+    }
+
+    /// Writes the sequence that opens the link, see [`Hyperlink::open`].
+    pub(crate) fn write_open<W: fmt::Write>(&self, f: &mut W) -> fmt::Result {
+        f.write_str("\x1b]8;")?;
+        if let Some(id) = &self.id {
+            f.write_str("id=")?;
+            write_percent_encoded(f, id, b":;=")?;
+        }
+        f.write_str(";")?;
+        f.write_str(&self.encoded_url)?;
+        f.write_str("\x1b\\")
+    }
+}
+
+impl fmt::Display for Hyperlink {
+    /// Writes the url as it was given.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.url)
+    }
+}
+
+/// Writes the text with the bytes outside of the printable ASCII characters
+/// and the `reserved` bytes percent encoded.
+fn write_percent_encoded<W: fmt::Write>(f: &mut W, text: &str, reserved: &[u8]) -> fmt::Result {
+    for byte in text.bytes() {
+        if (0x21..=0x7e).contains(&byte) && !reserved.contains(&byte) {
+            f.write_char(byte as char)?;
+        } else {
+            write!(f, "%{:02X}", byte)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ansi_code::parser::parse;
+
+    #[test]
+    fn encode_url() {
+        assert_eq!(Hyperlink::new("file:///tmp/ä b").open(), "\x1b]8;;file:///tmp/%C3%A4%20b\x1b\\");
+        assert_eq!(Hyperlink::new("http://x\x1b\\\x07").open(), "\x1b]8;;http://x%1B\\%07\x1b\\");
+        assert_eq!(Hyperlink::new("http://x?a=1;b=%41").open(), "\x1b]8;;http://x?a=1;b=%41\x1b\\");
+    }
+
+    #[test]
+    fn encode_id() {
+        assert_eq!(Hyperlink::new("http://x").id("a:b=c d").open(), "\x1b]8;id=a%3Ab%3Dc%20d;http://x\x1b\\");
+    }
+
+    #[test]
+    fn parser_reads_links() {
+        let link = Hyperlink::new("https://x.y/a;b").id("1");
+        let spans = parse(&format!("{}text{}", link.open(), CLOSE));
+        assert_eq!(spans[0].link.as_deref(), Some("https://x.y/a;b"));
+        assert_eq!(link.url(), "https://x.y/a;b");
+        assert_eq!(link.to_string(), "https://x.y/a;b");
+        assert_eq!(Hyperlink::new("http://x/\x1b[2J\x07").encoded_url(), "http://x/%1B[2J%07");
+    }
+}
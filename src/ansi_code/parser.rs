@@ -0,0 +1,548 @@
+//! A module for parsing painted strings.
+//!
+//! This module provides a struct `AnsiParser` that reads a string containing
+//! SGR escape sequences (`\x1b[..m`) and returns the text as a list of `Span`,
+//! each with the `SpanStyle` the terminal would show for it.
+//! OSC 8 hyperlinks are kept as the link of the spans inside them.
+//! Other escape sequences are removed from the text, unknown or malformed sequences are skipped.
+
+// =======================================================================
+
+use std::{ iter::Peekable, str::Chars };
+
+use super::{ hyperlink::{ Hyperlink, CLOSE }, ANSIEscapeCode };
+use crate::styles::{
+    basic_color::BasicColor,
+    color::Color,
+    formatter::Formatter,
+    paint_type::PaintType,
+    palette::PaletteColor,
+    rgb::Rgb,
+    span_style::SpanStyle,
+    underline_style::UnderlineStyle,
+    Styles,
+};
+
+// =======================================================================
+
+/// A struct representing a piece of text with its resolved style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    /// The text without any escape code.
+    pub text: String,
+    /// The style of the text.
+    pub style: SpanStyle,
+    /// The url of the OSC 8 hyperlink around the text, if there is one.
+    pub link: Option<String>,
+}
+
+/// A struct for parsing painted strings into spans.
+///
+/// The parser keeps the style between calls of `parse`,
+/// so a long output can be parsed piece by piece, for example line by line.
+#[derive(Debug, Clone, Default)]
+pub struct AnsiParser {
+    style: SpanStyle,
+    link: Option<String>,
+}
+
+/// Parses a painted string into spans.
+///
+/// # Example:
+/// ```
+/// use term_tools::ansi_code::parser::parse;
+/// let spans = parse("\x1b[1;31mError:\x1b[0m file not found");
+/// assert_eq!(spans.len(), 2);
+/// assert_eq!(spans[0].text, "Error:");
+/// assert_eq!(spans[1].text, " file not found");
+/// assert!(spans[1].style.is_plain());
+/// ```
+pub fn parse(input: &str) -> Vec<Span> {
+    AnsiParser::new().parse(input)
+}
+
+/// Paints spans back into a string, the inverse of `parse`.
+///
+/// Before each span only the parameters that change the style are written and OSC 8 sequences
+/// are written where the link changes. At the end the style is reset and the link closed.
+pub(crate) fn paint_spans(spans: &[Span]) -> String {
+    let mut output = String::new();
+    let mut style = SpanStyle::default();
+    let mut link: Option<&str> = None;
+    for span in spans {
+        if span.link.as_deref() != link {
+            link = span.link.as_deref();
+            output.push_str(&link.map_or(CLOSE.to_string(), |url| Hyperlink::new(url).open()));
+        }
+        let codes = style.transition_to(&span.style);
+        if !codes.is_empty() {
+            output.push_str(&ANSIEscapeCode::new(&codes.join(";")).code());
+        }
+        style = span.style;
+        output.push_str(&span.text);
+    }
+    if !style.is_plain() {
+        output.push_str(&ANSIEscapeCode::new("0").code());
+    }
+    if link.is_some() {
+        output.push_str(CLOSE);
+    }
+    output
+}
+
+impl AnsiParser {
+    /// Returns a parser that starts with the default style of the terminal.
+    pub fn new() -> Self {
+        AnsiParser::default()
+    }
+
+    /// Returns the style that is active after the parsed input.
+    pub fn style(&self) -> &SpanStyle {
+        &self.style
+    }
+
+    /// Returns the url of the hyperlink that is open after the parsed input.
+    pub fn link(&self) -> Option<&str> {
+        self.link.as_deref()
+    }
+
+    /// Parses the input into spans, starting with the style and link left by the previous input.
+    ///
+    /// Adjacent text with the same style and link is merged into one span and empty spans are not returned.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::ansi_code::parser::AnsiParser;
+    /// let mut parser = AnsiParser::new();
+    /// let spans = parser.parse("\x1b]8;;https://example.com\x1b\\docs\x1b]8;;\x1b\\ here");
+    /// assert_eq!(spans[0].link.as_deref(), Some("https://example.com"));
+    /// assert_eq!(spans[1].link, None);
+    /// ```
+    pub fn parse(&mut self, input: &str) -> Vec<Span> {
+        let mut spans: Vec<Span> = vec![];
+        let mut text = String::new();
+        let mut chars = input.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c != '\x1b' {
+                text.push(c);
+                continue;
+            }
+            match read_escape(&mut chars) {
+                Escape::Sgr(params) => {
+                    push_span(&mut spans, &mut text, &self.style, &self.link);
+                    self.apply_sgr(&params);
+                }
+                Escape::Osc(content) if content.starts_with("8;") => {
+                    push_span(&mut spans, &mut text, &self.style, &self.link);
+                    // `8;params;url`, an empty url closes the link
+                    let url = content.splitn(3, ';').nth(2).unwrap_or_default();
+                    self.link = if url.is_empty() { None } else { Some(url.to_string()) };
+                }
+                _ => {}
+            }
+        }
+        push_span(&mut spans, &mut text, &self.style, &self.link);
+        spans
+    }
+
+    /// Applies the parameters of one SGR sequence to the current style.
+    fn apply_sgr(&mut self, params: &str) {
+        let params: Vec<&str> = params.split(';').collect();
+        let mut i = 0;
+        while i < params.len() {
+            let param = params[i];
+            i += 1;
+            let mut sub = param.split(':');
+            let code = match sub.next().map(parse_number) {
+                Some(Some(code)) => code,
+                _ => {
+                    continue;
+                }
+            };
+            match code {
+                0 => {
+                    self.style = SpanStyle::default();
+                }
+                4 => {
+                    let style = match sub.next().map(parse_number) {
+                        Some(Some(0)) => Styles::StyleFormatter(Formatter { code: 24 }),
+                        Some(Some(2)) => Styles::StyleFormatter(Formatter { code: 21 }),
+                        Some(Some(c)) => {
+                            match u8::try_from(c).ok().and_then(UnderlineStyle::from_code) {
+                                Some(u) => Styles::StyleUnderline(u),
+                                None => Styles::StyleFormatter(Formatter { code: 4 }),
+                            }
+                        }
+                        _ => Styles::StyleFormatter(Formatter { code: 4 }),
+                    };
+                    self.style.apply(&style, &PaintType::FG);
+                }
+                1..=9 | 21..=29 | 50..=55 | 73..=75 => {
+                    self.style.apply(&Styles::StyleFormatter(Formatter { code: code as u8 }), &PaintType::FG);
+                }
+                30..=37 => {
+                    self.style.fg = basic((code - 30) as u8);
+                }
+                90..=97 => {
+                    self.style.fg = basic((code - 90 + 8) as u8);
+                }
+                40..=47 => {
+                    self.style.bg = basic((code - 40) as u8);
+                }
+                100..=107 => {
+                    self.style.bg = basic((code - 100 + 8) as u8);
+                }
+                39 => {
+                    self.style.fg = None;
+                }
+                49 => {
+                    self.style.bg = None;
+                }
+                59 => {
+                    self.style.ul = None;
+                }
+                38 | 48 | 58 => {
+                    let color = if param.contains(':') {
+                        extended_color(&sub.collect::<Vec<_>>(), true).0
+                    } else {
+                        let (color, used) = extended_color(&params[i..], false);
+                        i += used;
+                        color
+                    };
+                    match (code, color) {
+                        (38, Some(color)) => {
+                            self.style.fg = Some(color);
+                        }
+                        (48, Some(color)) => {
+                            self.style.bg = Some(color);
+                        }
+                        (58, Some(color)) => {
+                            self.style.ul = Some(color);
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// An enum representing an escape sequence that was read by `read_escape`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Escape {
+    /// An SGR sequence (`\x1b[..m`) with its parameters.
+    Sgr(String),
+    /// An OSC sequence (`\x1b]..\x07`) with its content.
+    Osc(String),
+    /// Any other sequence, or a malformed one.
+    Other,
+}
+
+/// Reads one escape sequence after its `\x1b`.
+///
+/// CSI sequences end with a final byte, OSC, DCS, SOS, PM and APC strings end with `BEL` or `\x1b\`
+/// and any other character after `\x1b` is a two character sequence.
+/// A character that can not be part of a CSI sequence ends it without consuming the character.
+pub(crate) fn read_escape(chars: &mut Peekable<Chars>) -> Escape {
+    match chars.next() {
+        Some('[') => read_csi(chars),
+        Some(']') => Escape::Osc(read_string(chars)),
+        Some('P' | 'X' | '^' | '_') => {
+            read_string(chars);
+            Escape::Other
+        }
+        _ => Escape::Other,
+    }
+}
+
+/// Reads a CSI sequence after `\x1b[`.
+fn read_csi(chars: &mut Peekable<Chars>) -> Escape {
+    let mut params = String::new();
+    let mut valid = true;
+    while let Some(&c) = chars.peek() {
+        match c {
+            '0'..='9' | ';' | ':' => params.push(c),
+            '<'..='?' | ' '..='/' => {
+                valid = false;
+            }
+            '@'..='~' => {
+                chars.next();
+                return if valid && c == 'm' { Escape::Sgr(params) } else { Escape::Other };
+            }
+            _ => {
+                return Escape::Other;
+            }
+        }
+        chars.next();
+    }
+    Escape::Other
+}
+
+/// Reads a control string, which ends with `BEL` or `\x1b\`, and returns its content.
+fn read_string(chars: &mut Peekable<Chars>) -> String {
+    let mut content = String::new();
+    while let Some(c) = chars.next() {
+        if c == '\x07' {
+            break;
+        }
+        if c == '\x1b' {
+            if chars.peek() == Some(&'\\') {
+                chars.next();
+            }
+            break;
+        }
+        content.push(c);
+    }
+    content
+}
+
+/// Returns the byte position of an escape sequence at the end of the input that is not complete yet.
+///
+/// Input that is read in chunks can end in the middle of a sequence,
+/// the rest of the sequence is then at the start of the next chunk.
+pub(crate) fn incomplete_escape(input: &str) -> Option<usize> {
+    let mut start = 0;
+    while let Some(i) = input[start..].find('\x1b') {
+        let position = start + i;
+        let rest = &input[position + 1..];
+        // the length of the sequence after its `\x1b`, `None` if it is not complete
+        let length = match rest.chars().next() {
+            None => None,
+            Some('[') => {
+                rest[1..].find(|c: char| !matches!(c, ' '..='?')).map(|e| {
+                    // a final byte belongs to the sequence, any other character only ends it
+                    if rest[1 + e..].starts_with(|c: char| matches!(c, '@'..='~')) { e + 2 } else { e + 1 }
+                })
+            }
+            Some(']' | 'P' | 'X' | '^' | '_') => {
+                match rest.find(['\x07', '\x1b']) {
+                    Some(e) if &rest[e..] == "\x1b" => None,
+                    Some(e) => Some(e + 1),
+                    None => None,
+                }
+            }
+            Some(c) => Some(c.len_utf8()),
+        };
+        match length {
+            Some(length) => {
+                start = position + 1 + length;
+            }
+            None => {
+                return Some(position);
+            }
+        }
+    }
+    None
+}
+
+/// Moves the collected text into a span, merging it with the last span if the style and link are the same.
+fn push_span(spans: &mut Vec<Span>, text: &mut String, style: &SpanStyle, link: &Option<String>) {
+    if text.is_empty() {
+        return;
+    }
+    match spans.last_mut() {
+        Some(last) if &last.style == style && &last.link == link => last.text.push_str(text),
+        _ => spans.push(Span { text: text.clone(), style: *style, link: link.clone() }),
+    }
+    text.clear();
+}
+
+/// Parses a parameter, an empty parameter is `0`.
+fn parse_number(value: &str) -> Option<u16> {
+    if value.is_empty() { Some(0) } else { value.parse().ok() }
+}
+
+/// Returns the basic color of the palette index.
+fn basic(index: u8) -> Option<Color> {
+    BasicColor::from_index(index).map(Color::Basic)
+}
+
+/// Parses the color after `38`, `48` or `58` and returns it with the number of used parameters.
+///
+/// `values` starts with the color mode, `5` for palette and `2` for rgb colors.
+/// In the colon form (`38:2::r:g:b`) the rgb values may follow a color space id.
+fn extended_color(values: &[&str], colon: bool) -> (Option<Color>, usize) {
+    let component = |i: usize| {
+        values
+            .get(i)
+            .and_then(|v| parse_number(v))
+            .and_then(|v| u8::try_from(v).ok())
+    };
+    match values.first().map(|v| parse_number(v)) {
+        Some(Some(5)) => (component(1).map(|index| Color::Palette(PaletteColor { index })), 2),
+        Some(Some(2)) => {
+            let start = if colon && values.len() > 4 { 2 } else { 1 };
+            let color = match (component(start), component(start + 1), component(start + 2)) {
+                (Some(r), Some(g), Some(b)) => Some(Color::Rgb(Rgb { r, g, b })),
+                _ => None,
+            };
+            (color, 4.min(values.len()))
+        }
+        Some(_) => (None, 1),
+        None => (None, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn red() -> Option<Color> {
+        Some(Color::Basic(BasicColor { fg: 31, bg: 41 }))
+    }
+
+    #[test]
+    fn parse_plain_text() {
+        let spans = parse("hello world");
+        assert_eq!(spans, vec![Span { text: "hello world".to_string(), style: SpanStyle::default(), link: None }]);
+        assert!(parse("").is_empty());
+    }
+
+    #[test]
+    fn parse_basic_colors_and_reset() {
+        let spans = parse("\x1b[31mred\x1b[0m plain \x1b[96;100mcyan");
+        assert_eq!(spans.len(), 3);
+        assert_eq!(spans[0].text, "red");
+        assert_eq!(spans[0].style.fg, red());
+        assert!(spans[1].style.is_plain());
+        assert_eq!(spans[2].style.fg, Some(Color::Basic(BasicColor { fg: 96, bg: 106 })));
+        assert_eq!(spans[2].style.bg, Some(Color::Basic(BasicColor { fg: 90, bg: 100 })));
+    }
+
+    #[test]
+    fn parse_combined_parameters() {
+        let spans = parse("\x1b[1;31;48;5;200mtext");
+        assert_eq!(spans.len(), 1);
+        let style = &spans[0].style;
+        assert_eq!(style.fg, red());
+        assert_eq!(style.bg, Some(Color::Palette(PaletteColor { index: 200 })));
+        assert_eq!(style.formatters.iter().collect::<Vec<_>>(), vec![Formatter { code: 1 }]);
+    }
+
+    #[test]
+    fn parse_rgb_colors() {
+        let spans = parse("\x1b[38;2;1;2;3;48:2::4:5:6;4mtext");
+        let style = &spans[0].style;
+        assert_eq!(style.fg, Some(Color::Rgb(Rgb { r: 1, g: 2, b: 3 })));
+        assert_eq!(style.bg, Some(Color::Rgb(Rgb { r: 4, g: 5, b: 6 })));
+        assert!(style.formatters.contains(&Formatter { code: 4 }));
+    }
+
+    #[test]
+    fn parse_underline_style_and_color() {
+        let spans = parse("\x1b[4:3;58;5;1ma\x1b[4:1;58:2::1:2:3mb\x1b[24;59mc");
+        assert_eq!(spans.len(), 3);
+        assert_eq!(spans[0].style.underline_style, Some(UnderlineStyle::Curly));
+        assert_eq!(spans[0].style.ul, Some(Color::Palette(PaletteColor { index: 1 })));
+        assert_eq!(spans[1].style.underline_style, None);
+        assert!(spans[1].style.formatters.contains(&Formatter { code: 4 }));
+        assert_eq!(spans[1].style.ul, Some(Color::Rgb(Rgb { r: 1, g: 2, b: 3 })));
+        assert!(spans[2].style.is_plain());
+        let spans = parse("\x1b[4:259ma");
+        assert_eq!(spans[0].style.underline_style, None);
+        assert!(spans[0].style.formatters.contains(&Formatter { code: 4 }));
+    }
+
+    #[test]
+    fn parse_off_codes() {
+        let spans = parse("\x1b[1;3;31;44ma\x1b[22;39mb\x1b[23;49mc");
+        assert_eq!(spans.len(), 3);
+        assert!(spans[1].style.formatters.contains(&Formatter { code: 3 }));
+        assert!(!spans[1].style.formatters.contains(&Formatter { code: 1 }));
+        assert_eq!(spans[1].style.fg, None);
+        assert!(spans[1].style.bg.is_some());
+        assert!(spans[2].style.is_plain());
+    }
+
+    #[test]
+    fn parse_empty_parameter_is_reset() {
+        let spans = parse("\x1b[31ma\x1b[mb");
+        assert!(spans[1].style.is_plain());
+    }
+
+    #[test]
+    fn merge_spans_with_same_style() {
+        let spans = parse("\x1b[31ma\x1b[31mb\x1b[2Jc");
+        assert_eq!(spans, vec![Span { text: "abc".to_string(), style: spans[0].style, link: None }]);
+    }
+
+    #[test]
+    fn skip_other_sequences() {
+        let spans = parse("a\x1b[2Kb\x1b]0;title\x07c\x1b]8;;http://x\x1b\\d\x1b7e");
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].text, "abc");
+        assert_eq!(spans[1].text, "de");
+        assert_eq!(spans[1].link.as_deref(), Some("http://x"));
+    }
+
+    #[test]
+    fn malformed_sequences_do_not_panic() {
+        let inputs = [
+            "\x1b",
+            "\x1b[",
+            "\x1b[31",
+            "\x1b[38;5m",
+            "\x1b[38;2;1m",
+            "\x1b[38;5;300m",
+            "\x1b[99999999999m",
+            "\x1b[?25hx",
+            "\x1b[3\u{e9}m",
+            "\x1b]8;;unterminated",
+            "\x1b[38:2m",
+        ];
+        for input in inputs {
+            let _ = parse(input);
+        }
+        assert_eq!(parse("a\x1b[3\u{e9}b")[0].text, "a\u{e9}b");
+        assert_eq!(parse("\x1b[38;5;300;1ma")[0].style.fg, None);
+    }
+
+    #[test]
+    fn read_escape_kinds() {
+        let read = |s: &str| read_escape(&mut s.chars().peekable());
+        assert_eq!(read("[1;31m"), Escape::Sgr("1;31".to_string()));
+        assert_eq!(read("[2J"), Escape::Other);
+        assert_eq!(read("]8;;http://x\x1b\\"), Escape::Osc("8;;http://x".to_string()));
+        assert_eq!(read("]0;title\x07"), Escape::Osc("0;title".to_string()));
+        assert_eq!(read("Pdata\x1b\\"), Escape::Other);
+    }
+
+    #[test]
+    fn keep_style_between_calls() {
+        let mut parser = AnsiParser::new();
+        parser.parse("\x1b[31mfirst");
+        let spans = parser.parse("second");
+        assert_eq!(spans[0].style.fg, red());
+        assert_eq!(parser.style().fg, red());
+    }
+
+    #[test]
+    fn parse_hyperlinks() {
+        let mut parser = AnsiParser::new();
+        let spans = parser.parse("a\x1b]8;id=1;http://x.y\x07\x1b[1mb\x1b]8;;\x07c");
+        assert_eq!(spans.len(), 3);
+        assert_eq!(spans[0].link, None);
+        assert_eq!(spans[1].link.as_deref(), Some("http://x.y"));
+        assert!(spans[1].style.formatters.contains(&Formatter { code: 1 }));
+        assert_eq!(spans[2].link, None);
+
+        parser.parse("\x1b]8;;http://z\x1b\\");
+        assert_eq!(parser.link(), Some("http://z"));
+        assert_eq!(parser.parse("d")[0].link.as_deref(), Some("http://z"));
+    }
+
+    #[test]
+    fn find_incomplete_escapes() {
+        assert_eq!(incomplete_escape("plain"), None);
+        assert_eq!(incomplete_escape("a\x1b[31mb"), None);
+        assert_eq!(incomplete_escape("a\x1b"), Some(1));
+        assert_eq!(incomplete_escape("a\x1b[38;5"), Some(1));
+        assert_eq!(incomplete_escape("\x1b[31\x1b[1"), Some(4));
+        assert_eq!(incomplete_escape("\x1b]8;;http://x"), Some(0));
+        assert_eq!(incomplete_escape("\x1b]8;;http://x\x1b"), Some(0));
+        assert_eq!(incomplete_escape("\x1b]8;;http://x\x1b\\é"), None);
+        assert_eq!(incomplete_escape("\x1bé\x1b("), None);
+    }
+}
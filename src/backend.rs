@@ -0,0 +1,475 @@
+//! A module for rendering styled text to other targets than escape codes.
+//!
+//! This module provides the trait `Backend`, which receives the resolved style and text of a
+//! `StyledText` or `StyledDocument` instead of escape codes, and four implementations:
+//! `AnsiBackend` writes escape codes, `PlainBackend` only the text, `HtmlBackend` HTML spans
+//! and `RecordingBackend` keeps every operation, so tests can check which styles were set.
+//! A backend for a terminal without escape codes, like the legacy Windows console,
+//! can implement the trait and set the console attributes in `set_style`.
+
+// =======================================================================
+
+use std::fmt;
+
+use crate::{
+    ansi_code::{ hyperlink::{ self, Hyperlink }, ANSIEscapeCode },
+    html::{ escape_html, has_link_scheme, HtmlRenderer },
+    styles::{ reset_mode::ResetMode, span_style::SpanStyle },
+};
+
+// =======================================================================
+
+/// A trait for targets that styled text can be rendered to.
+///
+/// `set_style` is only called when the style changes, the first time only if the first
+/// text has a style. `set_link` is only called when the link changes. `end` is called once
+/// after the last text, a link that is still open should be closed there too.
+pub trait Backend {
+    /// Sets the style of the text that is written next.
+    fn set_style(&mut self, style: &SpanStyle);
+
+    /// Sets the style with the escape codes that `StyledText::paint` writes for the change.
+    ///
+    /// `StyledText::render` calls this instead of `set_style`, also for codes that do not change
+    /// the style, like the reset at the end of a plain text in `SgrMode::Separate`.
+    /// A backend that writes escape codes can write `codes` as they are, so that its output
+    /// is the same as `paint`. By default `set_style` is called if the style changes.
+    fn set_style_with_codes(&mut self, previous: &SpanStyle, style: &SpanStyle, codes: &dyn fmt::Display) {
+        let _ = codes;
+        if previous != style {
+            self.set_style(style);
+        }
+    }
+
+    /// Sets the link of the text that is written next, `None` ends the link.
+    ///
+    /// By default links are ignored.
+    fn set_link(&mut self, link: Option<&Hyperlink>) {
+        let _ = link;
+    }
+
+    /// Writes text with the current style.
+    fn write_text(&mut self, text: &str);
+
+    /// Ends the output, the style that is still set should be turned off.
+    fn end(&mut self);
+}
+
+/// A backend that writes escape codes, with the fewest parameters needed for each change.
+///
+/// Links are written as OSC 8 hyperlinks. `Display` for `StyledText` renders through
+/// this backend straight into the formatter, so `paint` and the backend write the same.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ backend::AnsiBackend, styled, StyledDocument };
+/// let mut backend = AnsiBackend::new();
+/// let mut doc = StyledDocument::new();
+/// doc.push(styled("Stay").red().bold()).push(styled(" hungry").red());
+/// doc.render(&mut backend);
+/// assert_eq!(backend.output(), "\x1b[1;31mStay\x1b[22m hungry\x1b[0m");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AnsiBackend<W = String> {
+    output: W,
+    style: SpanStyle,
+    reset_mode: ResetMode,
+    link_open: bool,
+    failed: bool,
+}
+
+impl AnsiBackend {
+    /// Returns a backend with an empty output.
+    pub fn new() -> Self {
+        AnsiBackend::default()
+    }
+
+    /// Returns the written output.
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+}
+
+impl<W: fmt::Write> AnsiBackend<W> {
+    /// Returns a backend that writes into the writer.
+    pub(crate) fn with_writer(output: W) -> Self {
+        AnsiBackend {
+            output,
+            style: SpanStyle::default(),
+            reset_mode: ResetMode::default(),
+            link_open: false,
+            failed: false,
+        }
+    }
+
+    /// Sets how the style is turned off at the end of a document, see [`crate::StyledText::reset_mode`].
+    ///
+    /// A `StyledText` writes the reset of its own reset mode.
+    pub fn reset_mode(&mut self, mode: ResetMode) -> &mut Self {
+        self.reset_mode = mode;
+        self
+    }
+
+    /// Returns an error if writing into the writer failed.
+    pub(crate) fn result(&self) -> fmt::Result {
+        if self.failed { Err(fmt::Error) } else { Ok(()) }
+    }
+
+    /// Writes into the writer, after the first error nothing is written.
+    fn write(&mut self, args: fmt::Arguments<'_>) {
+        if !self.failed {
+            self.failed = self.output.write_fmt(args).is_err();
+        }
+    }
+
+    /// Writes the parameters as one escape code, if there are any.
+    fn write_codes(&mut self, codes: &[String]) {
+        if !codes.is_empty() {
+            let code = ANSIEscapeCode::new(&codes.join(";")).code();
+            self.write(format_args!("{}", code));
+        }
+    }
+}
+
+impl<W: fmt::Write> Backend for AnsiBackend<W> {
+    fn set_style(&mut self, style: &SpanStyle) {
+        let codes = self.style.transition_to(style);
+        self.write_codes(&codes);
+        self.style = *style;
+    }
+
+    fn set_style_with_codes(&mut self, _previous: &SpanStyle, style: &SpanStyle, codes: &dyn fmt::Display) {
+        self.write(format_args!("{}", codes));
+        self.style = *style;
+    }
+
+    fn set_link(&mut self, link: Option<&Hyperlink>) {
+        match link {
+            Some(link) => {
+                if !self.failed {
+                    self.failed = link.write_open(&mut self.output).is_err();
+                }
+                self.link_open = true;
+            }
+            None if self.link_open => {
+                self.write(format_args!("{}", hyperlink::CLOSE));
+                self.link_open = false;
+            }
+            None => {}
+        }
+    }
+
+    fn write_text(&mut self, text: &str) {
+        self.write(format_args!("{}", text));
+    }
+
+    fn end(&mut self) {
+        let codes = match self.reset_mode {
+            _ if self.style.is_plain() => vec![],
+            ResetMode::Full => vec!["0".to_string()],
+            ResetMode::Targeted => self.style.off_codes().iter().map(|c| c.to_string()).collect(),
+        };
+        self.write_codes(&codes);
+        self.style = SpanStyle::default();
+        self.set_link(None);
+    }
+}
+
+/// A backend that writes only the text.
+#[derive(Debug, Clone, Default)]
+pub struct PlainBackend {
+    output: String,
+}
+
+impl PlainBackend {
+    /// Returns a backend with an empty output.
+    pub fn new() -> Self {
+        PlainBackend::default()
+    }
+
+    /// Returns the written text.
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+}
+
+impl Backend for PlainBackend {
+    fn set_style(&mut self, _style: &SpanStyle) {}
+
+    fn write_text(&mut self, text: &str) {
+        self.output.push_str(text);
+    }
+
+    fn end(&mut self) {}
+}
+
+/// A backend that writes HTML spans with an `HtmlRenderer`.
+///
+/// Links with one of the schemes `HtmlRenderer::convert` allows become `<a>` elements,
+/// other links are left out.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ backend::HtmlBackend, html::HtmlRenderer, styled };
+/// let mut backend = HtmlBackend::new(HtmlRenderer::new());
+/// styled("a & b").italic().render(&mut backend);
+/// assert_eq!(backend.output(), "<span style=\"font-style:italic\">a &amp; b</span>");
+/// ```
+#[derive(Debug, Clone)]
+pub struct HtmlBackend {
+    renderer: HtmlRenderer,
+    output: String,
+    style: SpanStyle,
+    span_open: bool,
+    link: Option<String>,
+}
+
+impl HtmlBackend {
+    /// Returns a backend that writes spans the way the renderer does.
+    pub fn new(renderer: HtmlRenderer) -> Self {
+        HtmlBackend { renderer, output: String::new(), style: SpanStyle::default(), span_open: false, link: None }
+    }
+
+    /// Returns the written HTML.
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Closes the span that is open.
+    fn close_span(&mut self) {
+        if self.span_open {
+            self.output.push_str("</span>");
+            self.span_open = false;
+        }
+    }
+}
+
+impl Backend for HtmlBackend {
+    fn set_style(&mut self, style: &SpanStyle) {
+        if *style != self.style {
+            self.close_span();
+            self.style = *style;
+        }
+    }
+
+    fn set_link(&mut self, link: Option<&Hyperlink>) {
+        let url = link.map(|l| l.url()).filter(|url| has_link_scheme(url));
+        if url == self.link.as_deref() {
+            return;
+        }
+        // the span is closed before the link and opened again inside the new one
+        self.close_span();
+        if self.link.is_some() {
+            self.output.push_str("</a>");
+        }
+        if let Some(url) = url {
+            self.output.push_str(&format!("<a href=\"{}\">", escape_html(url)));
+        }
+        self.link = url.map(|url| url.to_string());
+    }
+
+    fn write_text(&mut self, text: &str) {
+        if !self.span_open {
+            if let Some(tag) = self.renderer.open_tag(&self.style) {
+                self.output.push_str(&tag);
+                self.span_open = true;
+            }
+        }
+        self.output.push_str(&escape_html(text));
+    }
+
+    fn end(&mut self) {
+        self.close_span();
+        self.set_link(None);
+        self.style = SpanStyle::default();
+    }
+}
+
+/// An enum representing an operation a `RecordingBackend` received.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    /// The style was set.
+    SetStyle(SpanStyle),
+    /// The link was set to the url, or ended with `None`.
+    SetLink(Option<String>),
+    /// Text was written.
+    Text(String),
+    /// The output was ended.
+    End,
+}
+
+/// A backend that keeps every operation, for tests.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ backend::{ Operation, RecordingBackend }, styled };
+/// let mut backend = RecordingBackend::new();
+/// styled("done").bold().render(&mut backend);
+/// let Operation::SetStyle(style) = &backend.operations()[0] else { panic!() };
+/// assert!(style.formatters.iter().any(|f| f.code == 1));
+/// assert_eq!(backend.text(), "done");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RecordingBackend {
+    operations: Vec<Operation>,
+}
+
+impl RecordingBackend {
+    /// Returns a backend without operations.
+    pub fn new() -> Self {
+        RecordingBackend::default()
+    }
+
+    /// Returns the operations in the order they were received.
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// Returns all written text.
+    pub fn text(&self) -> String {
+        self.operations
+            .iter()
+            .filter_map(|op| {
+                match op {
+                    Operation::Text(text) => Some(text.as_str()),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the pieces of text with the style they were written with.
+    pub fn spans(&self) -> Vec<(String, SpanStyle)> {
+        let mut style = SpanStyle::default();
+        let mut spans = vec![];
+        for op in &self.operations {
+            match op {
+                Operation::SetStyle(s) => {
+                    style = *s;
+                }
+                Operation::Text(text) => spans.push((text.clone(), style)),
+                Operation::SetLink(_) | Operation::End => {}
+            }
+        }
+        spans
+    }
+}
+
+impl Backend for RecordingBackend {
+    fn set_style(&mut self, style: &SpanStyle) {
+        self.operations.push(Operation::SetStyle(*style));
+    }
+
+    fn set_link(&mut self, link: Option<&Hyperlink>) {
+        self.operations.push(Operation::SetLink(link.map(|l| l.url().to_string())));
+    }
+
+    fn write_text(&mut self, text: &str) {
+        self.operations.push(Operation::Text(text.to_string()));
+    }
+
+    fn end(&mut self) {
+        self.operations.push(Operation::End);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ styled, styles::{ basic_color, paint_type::PaintType }, SgrMode, StyledDocument };
+
+    fn document() -> StyledDocument {
+        let mut doc = StyledDocument::new();
+        doc.text("a ").push(styled("b").red()).push(styled("c").red().bold());
+        doc
+    }
+
+    #[test]
+    fn ansi_backend_matches_paint() {
+        let mut backend = AnsiBackend::new();
+        document().render(&mut backend);
+        assert_eq!(backend.output(), document().paint());
+        let mut backend = AnsiBackend::new();
+        backend.reset_mode(ResetMode::Targeted);
+        let mut doc = StyledDocument::new();
+        doc.push(styled("a").italic());
+        doc.render(&mut backend);
+        assert_eq!(backend.output(), "\x1b[3ma\x1b[23m");
+    }
+
+    #[test]
+    fn ansi_backend_matches_styled_text_paint() {
+        let texts = [
+            styled("x").red().italic().reset_mode(ResetMode::Targeted).link("http://a").clone(),
+            styled("x").red().blue().bold().sgr_mode(SgrMode::Combined).link_with_id("http://a", "1").clone(),
+            styled("x").red().sgr_mode(SgrMode::Combined).reset_mode(ResetMode::Targeted).hyperlinks(false).clone(),
+            styled("plain").link("http://a").clone(),
+        ];
+        for mut text in texts {
+            let mut backend = AnsiBackend::new();
+            text.render(&mut backend);
+            assert_eq!(backend.output(), text.paint());
+        }
+        let mut backend = AnsiBackend::new();
+        styled("x").red().italic().reset_mode(ResetMode::Targeted).link("http://a").render(&mut backend);
+        assert_eq!(backend.output(), "\x1b]8;;http://a\x1b\\\x1b[31m\x1b[3mx\x1b[23m\x1b[39m\x1b]8;;\x1b\\");
+    }
+
+    #[test]
+    fn plain_backend_writes_text() {
+        let mut backend = PlainBackend::new();
+        document().render(&mut backend);
+        assert_eq!(backend.output(), "a bc");
+    }
+
+    #[test]
+    fn html_backend_writes_spans() {
+        let mut backend = HtmlBackend::new(HtmlRenderer::new());
+        document().render(&mut backend);
+        assert_eq!(
+            backend.output(),
+            "a <span style=\"color:#cd0000\">b</span><span style=\"color:#cd0000;font-weight:bold\">c</span>"
+        );
+    }
+
+    #[test]
+    fn html_backend_writes_links() {
+        let mut doc = StyledDocument::new();
+        doc.push(styled("a").red().link("https://x.y?a&b")).push(styled("b").link("javascript:x"));
+        let mut backend = HtmlBackend::new(HtmlRenderer::new());
+        doc.render(&mut backend);
+        assert_eq!(
+            backend.output(),
+            "<a href=\"https://x.y?a&amp;b\"><span style=\"color:#cd0000\">a</span></a>b"
+        );
+    }
+
+    #[test]
+    fn recording_backend_keeps_operations() {
+        let mut backend = RecordingBackend::new();
+        document().render(&mut backend);
+        let mut red = SpanStyle::default();
+        red.apply(&basic_color::RED, &PaintType::FG);
+        assert_eq!(backend.operations()[0], Operation::Text("a ".to_string()));
+        assert_eq!(backend.operations()[1], Operation::SetStyle(red));
+        assert_eq!(backend.operations().last(), Some(&Operation::End));
+        assert_eq!(backend.operations().len(), 6);
+        assert_eq!(backend.spans()[1], ("b".to_string(), red));
+        assert_eq!(backend.text(), "a bc");
+
+        let mut backend = RecordingBackend::new();
+        let mut doc = document();
+        doc.push(styled("d").link("http://x"));
+        doc.render(&mut backend);
+        assert_eq!(backend.operations()[5], Operation::SetLink(Some("http://x".to_string())));
+        assert_eq!(backend.operations()[7], Operation::Text("d".to_string()));
+        let mut backend = RecordingBackend::new();
+        styled("e").link("http://y").render(&mut backend);
+        assert_eq!(backend.operations(), [
+            Operation::SetLink(Some("http://y".to_string())),
+            Operation::Text("e".to_string()),
+            Operation::SetLink(None),
+            Operation::End,
+        ]);
+    }
+}
@@ -0,0 +1,262 @@
+//! A module for detecting the color capability of the terminal.
+//!
+//! This module reads the well known environment variables (`NO_COLOR`, `CLICOLOR`,
+//! `CLICOLOR_FORCE`, `COLORTERM` and `TERM`) and checks if the output stream is a terminal,
+//! then returns the `ColorLevel` that `StyledText::color_level` should use.
+//! It also guesses from the variables terminals set if they support OSC 8 hyperlinks,
+//! for `StyledText::hyperlinks`.
+
+// =======================================================================
+
+use std::io::{ self, IsTerminal };
+
+use crate::styles::color_level::ColorLevel;
+
+// =======================================================================
+
+/// Returns the color level of the standard output.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ detect, styled };
+/// let styled_text = styled("Be tolerant with others and strict with yourself.")
+///     .red()
+///     .color_level(detect::stdout_color_level())
+///     .paint();
+/// ```
+/// when the output is redirected into a file or a pipe, the text is painted without escape codes.
+pub fn stdout_color_level() -> ColorLevel {
+    color_level_from(|key| std::env::var(key).ok(), io::stdout().is_terminal())
+}
+
+/// Returns the color level of the standard error.
+pub fn stderr_color_level() -> ColorLevel {
+    color_level_from(|key| std::env::var(key).ok(), io::stderr().is_terminal())
+}
+
+/// Returns the color level for the given environment and terminal state.
+///
+/// `env` is used to look up environment variables, so any source of variables can be injected.
+/// `is_terminal` says if the output stream is a terminal.
+///
+/// # Example:
+/// ```
+/// use std::collections::HashMap;
+/// use term_tools::{ detect, ColorLevel };
+/// let env = HashMap::from([("TERM", "xterm-256color")]);
+/// let level = detect::color_level_from(|key| env.get(key).map(|v| v.to_string()), true);
+/// assert_eq!(level, ColorLevel::Ansi256);
+/// ```
+///
+/// The rules are checked in this order:
+///
+/// * a non empty `NO_COLOR` disables colors.
+/// * a `CLICOLOR_FORCE` other than `0` enables at least 16 colors, even if the output is not a terminal.
+/// * otherwise colors are disabled when the output is not a terminal, `CLICOLOR` is `0` or `TERM` is `dumb`.
+/// * `COLORTERM=truecolor`, `COLORTERM=24bit` or a `TERM` like `xterm-direct` enables 24 bit colors.
+/// * a `TERM` like `xterm-256color` enables 256 colors.
+/// * any other `TERM` or `COLORTERM` enables 16 colors.
+pub fn color_level_from<E>(env: E, is_terminal: bool) -> ColorLevel where E: Fn(&str) -> Option<String> {
+    let var = |key: &str| env(key).filter(|v| !v.is_empty());
+
+    if var("NO_COLOR").is_some() {
+        return ColorLevel::NoColor;
+    }
+    let forced = var("CLICOLOR_FORCE").is_some_and(|v| v != "0");
+    let min_level = if forced { ColorLevel::Ansi16 } else { ColorLevel::NoColor };
+
+    if !forced && (!is_terminal || var("CLICOLOR").is_some_and(|v| v == "0")) {
+        return ColorLevel::NoColor;
+    }
+
+    let term = var("TERM").map(|t| t.to_lowercase());
+    let colorterm = var("COLORTERM").map(|t| t.to_lowercase());
+
+    if term.as_deref() == Some("dumb") {
+        return min_level;
+    }
+    if matches!(colorterm.as_deref(), Some("truecolor" | "24bit")) {
+        return ColorLevel::TrueColor;
+    }
+
+    let level = match term {
+        Some(term) if term.ends_with("-direct") || term.contains("truecolor") || term.contains("24bit") => {
+            ColorLevel::TrueColor
+        }
+        Some(term) if term.contains("256") => ColorLevel::Ansi256,
+        Some(_) => ColorLevel::Ansi16,
+        None if colorterm.is_some() || cfg!(windows) => ColorLevel::Ansi16,
+        None => ColorLevel::NoColor,
+    };
+    level.max(min_level)
+}
+
+/// Returns `true` if the standard output is a terminal that supports hyperlinks.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ detect, styled };
+/// let styled_text = styled("the docs")
+///     .link("https://docs.rs/term_tools")
+///     .hyperlinks(detect::stdout_hyperlinks())
+///     .paint();
+/// ```
+/// when the terminal does not support them, the url is written after the text.
+/// `StyledText::detect_hyperlinks` does the same.
+pub fn stdout_hyperlinks() -> bool {
+    hyperlinks_from(|key| std::env::var(key).ok(), io::stdout().is_terminal())
+}
+
+/// Returns `true` if the standard error is a terminal that supports hyperlinks.
+pub fn stderr_hyperlinks() -> bool {
+    hyperlinks_from(|key| std::env::var(key).ok(), io::stderr().is_terminal())
+}
+
+/// Returns `true` if the terminal of the given environment supports hyperlinks.
+///
+/// `env` is used to look up environment variables and `is_terminal` says if
+/// the output stream is a terminal, like in [`color_level_from`].
+///
+/// # Example:
+/// ```
+/// use std::collections::HashMap;
+/// use term_tools::detect;
+/// let env = HashMap::from([("TERM_PROGRAM", "WezTerm")]);
+/// assert!(detect::hyperlinks_from(|key| env.get(key).map(|v| v.to_string()), true));
+/// assert!(!detect::hyperlinks_from(|key| env.get(key).map(|v| v.to_string()), false));
+/// ```
+///
+/// The rules are checked in this order:
+///
+/// * `FORCE_HYPERLINK` other than `0` enables hyperlinks and `0` disables them, even if the output is not a terminal.
+/// * otherwise hyperlinks are disabled when the output is not a terminal or `TERM` is `dumb`.
+/// * `TERM_PROGRAM` of iTerm2, WezTerm, VS Code, Hyper or ghostty enables hyperlinks.
+/// * `WT_SESSION` (Windows Terminal), `KITTY_WINDOW_ID`, `KONSOLE_VERSION` or
+///   a `TERM` like `xterm-kitty`, `wezterm`, `foot` or `alacritty` enables hyperlinks.
+/// * a `VTE_VERSION` of 5000 or higher (GNOME Terminal and other VTE terminals) enables hyperlinks.
+pub fn hyperlinks_from<E>(env: E, is_terminal: bool) -> bool where E: Fn(&str) -> Option<String> {
+    let var = |key: &str| env(key).filter(|v| !v.is_empty());
+
+    if let Some(force) = var("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+    let term = var("TERM").map(|t| t.to_lowercase());
+    if !is_terminal || term.as_deref() == Some("dumb") {
+        return false;
+    }
+
+    let program = var("TERM_PROGRAM").map(|p| p.to_lowercase());
+    if matches!(program.as_deref(), Some("iterm.app" | "wezterm" | "vscode" | "hyper" | "ghostty")) {
+        return true;
+    }
+    if var("WT_SESSION").is_some() || var("KITTY_WINDOW_ID").is_some() || var("KONSOLE_VERSION").is_some() {
+        return true;
+    }
+    if term.is_some_and(|t| ["kitty", "wezterm", "foot", "alacritty", "ghostty"].iter().any(|n| t.contains(n))) {
+        return true;
+    }
+    var("VTE_VERSION")
+        .and_then(|v| v.parse::<u32>().ok())
+        .is_some_and(|v| v >= 5000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn level(vars: &[(&str, &str)], is_terminal: bool) -> ColorLevel {
+        let env: HashMap<&str, &str> = vars.iter().cloned().collect();
+        color_level_from(|key| env.get(key).map(|v| v.to_string()), is_terminal)
+    }
+
+    #[test]
+    fn no_color_wins() {
+        assert_eq!(level(&[("NO_COLOR", "1"), ("COLORTERM", "truecolor")], true), ColorLevel::NoColor);
+        assert_eq!(level(&[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")], false), ColorLevel::NoColor);
+    }
+
+    #[test]
+    fn empty_no_color_is_ignored() {
+        assert_eq!(level(&[("NO_COLOR", ""), ("TERM", "xterm")], true), ColorLevel::Ansi16);
+    }
+
+    #[test]
+    fn not_a_terminal() {
+        assert_eq!(level(&[("TERM", "xterm-256color")], false), ColorLevel::NoColor);
+    }
+
+    #[test]
+    fn clicolor_force() {
+        assert_eq!(level(&[("CLICOLOR_FORCE", "1")], false), ColorLevel::Ansi16);
+        assert_eq!(level(&[("CLICOLOR_FORCE", "1"), ("TERM", "xterm-256color")], false), ColorLevel::Ansi256);
+        assert_eq!(level(&[("CLICOLOR_FORCE", "1"), ("TERM", "dumb")], false), ColorLevel::Ansi16);
+        assert_eq!(level(&[("CLICOLOR_FORCE", "0"), ("TERM", "xterm")], false), ColorLevel::NoColor);
+    }
+
+    #[test]
+    fn clicolor_disabled() {
+        assert_eq!(level(&[("CLICOLOR", "0"), ("TERM", "xterm")], true), ColorLevel::NoColor);
+        assert_eq!(level(&[("CLICOLOR", "1"), ("TERM", "xterm")], true), ColorLevel::Ansi16);
+    }
+
+    #[test]
+    fn dumb_terminal() {
+        assert_eq!(level(&[("TERM", "dumb")], true), ColorLevel::NoColor);
+    }
+
+    #[test]
+    fn true_color() {
+        assert_eq!(level(&[("COLORTERM", "truecolor"), ("TERM", "xterm")], true), ColorLevel::TrueColor);
+        assert_eq!(level(&[("COLORTERM", "24bit")], true), ColorLevel::TrueColor);
+        assert_eq!(level(&[("TERM", "xterm-direct")], true), ColorLevel::TrueColor);
+    }
+
+    #[test]
+    fn palette_256() {
+        assert_eq!(level(&[("TERM", "xterm-256color")], true), ColorLevel::Ansi256);
+        assert_eq!(level(&[("TERM", "tmux-256color")], true), ColorLevel::Ansi256);
+        assert_eq!(level(&[("TERM", "screen-256color")], true), ColorLevel::Ansi256);
+    }
+
+    #[test]
+    fn basic_16() {
+        assert_eq!(level(&[("TERM", "xterm")], true), ColorLevel::Ansi16);
+        assert_eq!(level(&[("TERM", "linux")], true), ColorLevel::Ansi16);
+        assert_eq!(level(&[("COLORTERM", "yes")], true), ColorLevel::Ansi16);
+    }
+
+    #[test]
+    fn missing_term() {
+        let expected = if cfg!(windows) { ColorLevel::Ansi16 } else { ColorLevel::NoColor };
+        assert_eq!(level(&[], true), expected);
+    }
+
+    fn hyperlinks(vars: &[(&str, &str)], is_terminal: bool) -> bool {
+        let env: HashMap<&str, &str> = vars.iter().cloned().collect();
+        hyperlinks_from(|key| env.get(key).map(|v| v.to_string()), is_terminal)
+    }
+
+    #[test]
+    fn hyperlink_terminals() {
+        assert!(hyperlinks(&[("TERM_PROGRAM", "iTerm.app")], true));
+        assert!(hyperlinks(&[("WT_SESSION", "1234")], true));
+        assert!(hyperlinks(&[("TERM", "xterm-kitty")], true));
+        assert!(hyperlinks(&[("VTE_VERSION", "6003"), ("TERM", "xterm-256color")], true));
+        assert!(!hyperlinks(&[("VTE_VERSION", "4205")], true));
+        assert!(!hyperlinks(&[("TERM", "xterm-256color")], true));
+        assert!(!hyperlinks(&[], true));
+    }
+
+    #[test]
+    fn hyperlinks_need_a_terminal() {
+        assert!(!hyperlinks(&[("TERM_PROGRAM", "WezTerm")], false));
+        assert!(!hyperlinks(&[("TERM", "dumb"), ("WT_SESSION", "1")], true));
+    }
+
+    #[test]
+    fn force_hyperlink() {
+        assert!(hyperlinks(&[("FORCE_HYPERLINK", "1")], false));
+        assert!(!hyperlinks(&[("FORCE_HYPERLINK", "0"), ("TERM_PROGRAM", "WezTerm")], true));
+    }
+}
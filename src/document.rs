@@ -0,0 +1,286 @@
+//! A module for building text out of many styled fragments.
+//!
+//! This module provides a struct `StyledDocument` that holds a list of `StyledText` fragments.
+//! Fragments can be nested, the inner fragments inherit the styles of the outer ones,
+//! and the whole document is painted as one stream with the fewest escape codes needed.
+
+// =======================================================================
+
+use crate::{
+    ansi_code::hyperlink::Hyperlink,
+    backend::{ AnsiBackend, Backend },
+    styles::{ color_level::ColorLevel, reset_mode::ResetMode, span_style::SpanStyle },
+    StyledText,
+};
+
+// =======================================================================
+
+/// A struct representing a text made of many styled fragments.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ styled, StyledDocument };
+/// let mut line = StyledDocument::new();
+/// line.push(styled("error:").red().bold())
+///     .text(" cannot open ")
+///     .push(styled("src/main.rs").underline());
+/// assert_eq!(
+///     line.paint(),
+///     "\x1b[1;31merror:\x1b[0m cannot open \x1b[4msrc/main.rs\x1b[0m"
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StyledDocument {
+    fragments: Vec<Fragment>,
+    color_level: ColorLevel,
+    reset_mode: ResetMode,
+}
+
+/// A struct representing a styled text with the fragments nested inside it.
+#[derive(Debug, Clone)]
+struct Fragment {
+    styled: StyledText,
+    children: Vec<Fragment>,
+}
+
+impl StyledDocument {
+    /// Returns an empty document.
+    pub fn new() -> Self {
+        StyledDocument::default()
+    }
+
+    /// Adds a text without any style.
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.push(&StyledText::new(text.to_string()))
+    }
+
+    /// Adds a styled text.
+    pub fn push(&mut self, styled: &StyledText) -> &mut Self {
+        self.fragments.push(Fragment { styled: styled.clone(), children: vec![] });
+        self
+    }
+
+    /// Adds a styled text followed by the fragments of another document,
+    /// which inherit the styles of the styled text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::{ styled, StyledDocument };
+    /// let mut inner = StyledDocument::new();
+    /// inner.text("unused variable ").push(styled("x").bold());
+    /// let mut line = StyledDocument::new();
+    /// line.nest(styled("warning: ").yellow(), &inner);
+    /// assert_eq!(line.paint(), "\x1b[33mwarning: unused variable \x1b[1mx\x1b[0m");
+    /// ```
+    pub fn nest(&mut self, outer: &StyledText, inner: &StyledDocument) -> &mut Self {
+        self.fragments.push(Fragment { styled: outer.clone(), children: inner.fragments.clone() });
+        self
+    }
+
+    /// Sets the color level that `paint` should respect, see [`StyledText::color_level`].
+    ///
+    /// The color levels of the fragments themselves are ignored.
+    pub fn color_level(&mut self, level: ColorLevel) -> &mut Self {
+        self.color_level = level;
+        self
+    }
+
+    /// Sets how the styles are turned off at the end of the document, see [`StyledText::reset_mode`].
+    pub fn reset_mode(&mut self, mode: ResetMode) -> &mut Self {
+        self.reset_mode = mode;
+        self
+    }
+
+    /// Paints the document.
+    ///
+    /// Before each piece of text only the parameters that change the style of the previous
+    /// piece are written, combined in one escape code.
+    pub fn paint(&self) -> String {
+        let mut backend = AnsiBackend::new();
+        backend.reset_mode(self.reset_mode);
+        self.render(&mut backend);
+        backend.output().to_string()
+    }
+
+    /// Renders the document through a backend, see the [`crate::backend`] module.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::{ backend::PlainBackend, styled, StyledDocument };
+    /// let mut line = StyledDocument::new();
+    /// line.push(styled("error:").red()).text(" disk full");
+    /// let mut backend = PlainBackend::new();
+    /// line.render(&mut backend);
+    /// assert_eq!(backend.output(), "error: disk full");
+    /// ```
+    pub fn render<B: Backend + ?Sized>(&self, backend: &mut B) {
+        let mut current = SpanStyle::default();
+        let mut current_link = None;
+        for (text, style, link) in self.spans() {
+            if link != current_link {
+                backend.set_link(link);
+                current_link = link;
+            }
+            if style != current {
+                backend.set_style(&style);
+                current = style;
+            }
+            backend.write_text(text);
+        }
+        backend.end();
+    }
+
+    /// Returns the pieces of text of the document in order, each with the style it is shown with
+    /// and the link it belongs to.
+    ///
+    /// Empty pieces are left out. A link that can not be shown as a hyperlink, because
+    /// hyperlinks are not supported or the color level is `NoColor`, is written after its
+    /// text as ` (url)`.
+    pub(crate) fn spans(&self) -> Vec<(&str, SpanStyle, Option<&Hyperlink>)> {
+        let mut spans = vec![];
+        for fragment in &self.fragments {
+            self.collect_spans(fragment, &SpanStyle::default(), None, &mut spans);
+        }
+        spans
+    }
+
+    /// Collects the pieces of a fragment and its children on top of the style and link of its parent.
+    fn collect_spans<'a>(
+        &self,
+        fragment: &'a Fragment,
+        parent: &SpanStyle,
+        parent_link: Option<&'a Hyperlink>,
+        spans: &mut Vec<(&'a str, SpanStyle, Option<&'a Hyperlink>)>
+    ) {
+        let mut style = *parent;
+        if self.color_level != ColorLevel::NoColor {
+            for (s, p) in fragment.styled.resolved_styles(self.color_level) {
+                style.apply(&s, &p);
+            }
+        }
+        let hyperlinks = fragment.styled.hyperlinks && self.color_level != ColorLevel::NoColor;
+        let link = match &fragment.styled.link {
+            Some(link) if hyperlinks => Some(link),
+            _ => parent_link,
+        };
+        let runs = match &fragment.styled.gradient {
+            Some(gradient) => gradient.runs(&fragment.styled.text, self.color_level),
+            None => vec![(fragment.styled.text.as_str(), None)],
+        };
+        for (run, color) in runs.into_iter().filter(|(run, _)| !run.is_empty()) {
+            let mut run_style = style;
+            if color.is_some() {
+                run_style.fg = color;
+            }
+            spans.push((run, run_style, link));
+        }
+        for child in &fragment.children {
+            self.collect_spans(child, &style, link, spans);
+        }
+        if let Some(fallback) = fragment.styled.link.as_ref().filter(|_| !hyperlinks) {
+            for text in [" (", fallback.encoded_url(), ")"] {
+                spans.push((text, *parent, parent_link));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ gradient::Gradient, styled, styles::rgb::Rgb };
+
+    #[test]
+    fn paint_empty_document() {
+        assert_eq!(StyledDocument::new().paint(), "");
+    }
+
+    #[test]
+    fn paint_plain_fragments() {
+        let mut doc = StyledDocument::new();
+        doc.text("a").text("b");
+        assert_eq!(doc.paint(), "ab");
+    }
+
+    #[test]
+    fn paint_fragments_with_shared_styles() {
+        let mut doc = StyledDocument::new();
+        doc.push(styled("a").red().bold())
+            .push(styled("b").red())
+            .push(styled("c").blue().bold());
+        assert_eq!(doc.paint(), "\x1b[1;31ma\x1b[22mb\x1b[1;34mc\x1b[0m");
+    }
+
+    #[test]
+    fn paint_nested_fragments() {
+        let mut deepest = StyledDocument::new();
+        deepest.push(styled("c").underline());
+        let mut inner = StyledDocument::new();
+        inner.nest(styled("b").bold(), &deepest).text("d");
+        let mut doc = StyledDocument::new();
+        doc.nest(styled("a").red(), &inner).text("e");
+        assert_eq!(doc.paint(), "\x1b[31ma\x1b[1mb\x1b[4mc\x1b[0;31md\x1b[0me");
+    }
+
+    #[test]
+    fn paint_nested_background() {
+        let mut inner = StyledDocument::new();
+        inner.push(styled("b").green().fg());
+        let mut doc = StyledDocument::new();
+        doc.nest(styled("a").blue().bg(), &inner);
+        assert_eq!(doc.paint(), "\x1b[44ma\x1b[32mb\x1b[0m");
+    }
+
+    #[test]
+    fn paint_with_targeted_reset() {
+        let mut doc = StyledDocument::new();
+        doc.push(styled("a").red().italic()).reset_mode(ResetMode::Targeted);
+        assert_eq!(doc.paint(), "\x1b[3;31ma\x1b[23;39m");
+    }
+
+    #[test]
+    fn paint_gradient_fragment() {
+        let fade = Gradient::new(&[Rgb { r: 255, g: 0, b: 0 }, Rgb { r: 0, g: 0, b: 255 }]);
+        let mut doc = StyledDocument::new();
+        doc.push(styled("ab").bold().gradient(&fade)).push(styled("c").bold());
+        assert_eq!(doc.paint(), "\x1b[1;38;2;255;0;0ma\x1b[38;2;0;0;255mb\x1b[39mc\x1b[0m");
+    }
+
+    #[test]
+    fn paint_with_color_level() {
+        let mut doc = StyledDocument::new();
+        doc.push(styled("a").rgb(255, 0, 0).bold()).color_level(ColorLevel::Ansi256);
+        assert_eq!(doc.paint(), "\x1b[1;38;5;196ma\x1b[0m");
+        doc.color_level(ColorLevel::NoColor);
+        assert_eq!(doc.paint(), "a");
+    }
+
+    #[test]
+    fn paint_links() {
+        let mut doc = StyledDocument::new();
+        doc.push(styled("x").red().link("http://a")).text(" y");
+        assert_eq!(doc.paint(), "\x1b]8;;http://a\x1b\\\x1b[31mx\x1b]8;;\x1b\\\x1b[0m y");
+
+        let mut inner = StyledDocument::new();
+        inner.push(styled("b").bold());
+        let mut doc = StyledDocument::new();
+        doc.nest(styled("a").link_with_id("http://a", "1"), &inner).push(styled("c").link("http://c"));
+        assert_eq!(
+            doc.paint(),
+            "\x1b]8;id=1;http://a\x1b\\a\x1b[1mb\x1b]8;;http://c\x1b\\\x1b[0mc\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn paint_links_as_text() {
+        let mut doc = StyledDocument::new();
+        doc.push(styled("x").red().link("http://a").hyperlinks(false)).text(" y");
+        assert_eq!(doc.paint(), "\x1b[31mx\x1b[0m (http://a) y");
+        doc.color_level(ColorLevel::NoColor);
+        assert_eq!(doc.paint(), "x (http://a) y");
+
+        let mut doc = StyledDocument::new();
+        doc.push(styled("x").link("http://x/\x1b[2J\x07")).color_level(ColorLevel::NoColor);
+        assert_eq!(doc.paint(), "x (http://x/%1B[2J%07)");
+    }
+}
@@ -0,0 +1,323 @@
+//! A module for painting text with gradients.
+//!
+//! This module provides a struct `Gradient` that fades through a list of color stops.
+//! A gradient is set on a `StyledText` with `StyledText::gradient`, then every grapheme
+//! is painted with its own color and graphemes of the same color share one escape code.
+
+// =======================================================================
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    styles::{
+        color::Color,
+        color_level::ColorLevel,
+        color_space::{ linear_to_srgb, srgb_to_linear, Hsl, Oklab },
+        rgb::Rgb,
+    },
+    visible_width,
+};
+
+// =======================================================================
+
+/// An enum representing the color space the colors between two stops are mixed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColorSpace {
+    /// Mixes the sRGB channels, like CSS gradients do by default.
+    #[default]
+    Srgb,
+    /// Mixes the channels in linear light, which keeps the middle of the gradient bright.
+    LinearRgb,
+    /// Mixes in the perceptual OKLab color space, which gives the most even fade.
+    Oklab,
+    /// Mixes hue, saturation and lightness, the hue takes the shorter way around the color wheel.
+    Hsl,
+}
+
+/// An enum representing which way the gradient runs through the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Direction {
+    /// Each line fades from the first to the last stop, one color per grapheme.
+    ///
+    /// The color of a grapheme follows the terminal column it starts at, so the columns of all
+    /// lines line up, also with wide characters, and the longest line reaches the last stop.
+    #[default]
+    Horizontal,
+    /// The lines fade from the first to the last stop, one color per line.
+    Vertical,
+}
+
+/// A struct representing a gradient through a list of color stops.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ styled, gradient::{ ColorSpace, Gradient } };
+/// use term_tools::styles::rgb::Rgb;
+/// let mut fade = Gradient::new(&[Rgb { r: 255, g: 0, b: 0 }, Rgb { r: 0, g: 0, b: 255 }]);
+/// fade.space(ColorSpace::Oklab);
+/// let banner = styled("term_tools").gradient(&fade).paint();
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Gradient {
+    stops: Vec<Rgb>,
+    space: ColorSpace,
+    direction: Direction,
+}
+
+impl Gradient {
+    /// Returns a gradient through the stops, spread evenly over the text.
+    ///
+    /// With one stop the whole text has that color, without stops the text is not colored.
+    pub fn new(stops: &[Rgb]) -> Self {
+        Gradient { stops: stops.to_vec(), ..Gradient::default() }
+    }
+
+    /// Returns a gradient through the colors of the rainbow, from red to violet.
+    pub fn rainbow() -> Self {
+        let mut rainbow = Gradient::new(&[0.0, 60.0, 120.0, 180.0, 240.0, 300.0].map(|h| Rgb::from_hsl(h, 1.0, 0.5)));
+        rainbow.space(ColorSpace::Hsl);
+        rainbow
+    }
+
+    /// Returns a gradient through the pastel colors of the rainbow, which are easier to read
+    /// on a light background.
+    pub fn pastel_rainbow() -> Self {
+        let mut rainbow = Gradient::new(&[0.0, 60.0, 120.0, 180.0, 240.0, 300.0].map(|h| Rgb::from_hsl(h, 0.9, 0.8)));
+        rainbow.space(ColorSpace::Hsl);
+        rainbow
+    }
+
+    /// Sets the color space the colors between two stops are mixed in.
+    ///
+    /// **the default color space is `ColorSpace::Srgb`**
+    pub fn space(&mut self, space: ColorSpace) -> &mut Self {
+        self.space = space;
+        self
+    }
+
+    /// Sets which way the gradient runs through the text.
+    ///
+    /// **the default direction is `Direction::Horizontal`**
+    pub fn direction(&mut self, direction: Direction) -> &mut Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Returns the color at the position `t` of the gradient, from 0.0 for the first stop
+    /// to 1.0 for the last one, or `None` if the gradient has no stops.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::gradient::Gradient;
+    /// use term_tools::styles::rgb::Rgb;
+    /// let fade = Gradient::new(&[Rgb { r: 0, g: 0, b: 0 }, Rgb { r: 255, g: 255, b: 255 }]);
+    /// assert_eq!(fade.at(0.5), Some(Rgb { r: 128, g: 128, b: 128 }));
+    /// ```
+    pub fn at(&self, t: f64) -> Option<Rgb> {
+        let last = self.stops.len().checked_sub(1)?;
+        if last == 0 {
+            return Some(self.stops[0]);
+        }
+        let scaled = t.clamp(0.0, 1.0) * last as f64;
+        let i = (scaled.floor() as usize).min(last - 1);
+        Some(self.mix(&self.stops[i], &self.stops[i + 1], scaled - i as f64))
+    }
+
+    /// Mixes two colors in the color space of the gradient.
+    fn mix(&self, from: &Rgb, to: &Rgb, t: f64) -> Rgb {
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+        match self.space {
+            ColorSpace::Srgb => from.mix(to, t),
+            ColorSpace::LinearRgb => {
+                let channel = |a: u8, b: u8| linear_to_srgb(lerp(srgb_to_linear(a), srgb_to_linear(b)));
+                Rgb { r: channel(from.r, to.r), g: channel(from.g, to.g), b: channel(from.b, to.b) }
+            }
+            ColorSpace::Oklab => {
+                let (a, b): (Oklab, Oklab) = (from.to_oklab(), to.to_oklab());
+                Rgb::from_oklab(lerp(a.l, b.l), lerp(a.a, b.a), lerp(a.b, b.b))
+            }
+            ColorSpace::Hsl => {
+                let (mut a, mut b): (Hsl, Hsl) = (from.to_hsl(), to.to_hsl());
+                // grays have no hue, so they take the hue of the other color
+                if a.s == 0.0 {
+                    a.h = b.h;
+                }
+                if b.s == 0.0 {
+                    b.h = a.h;
+                }
+                let mut delta = b.h - a.h;
+                if delta > 180.0 {
+                    delta -= 360.0;
+                } else if delta < -180.0 {
+                    delta += 360.0;
+                }
+                Rgb::from_hsl(a.h + delta * t, lerp(a.s, b.s), lerp(a.l, b.l))
+            }
+        }
+    }
+
+    /// Splits the text into runs of graphemes with the same color at the given color level.
+    ///
+    /// Horizontally the position of a grapheme is the column it starts at,
+    /// measured with `visible_width`, so wide characters take two columns.
+    /// Line breaks keep the color of the grapheme before them, so they never start a run.
+    /// On `NoColor` or without stops the colors are `None`.
+    pub(crate) fn runs<'a>(&self, text: &'a str, level: ColorLevel) -> Vec<(&'a str, Option<Color>)> {
+        let lines: Vec<&str> = text.split_inclusive('\n').collect();
+        // the column the last grapheme of the line starts at
+        let last_column = |line: &str| {
+            line.trim_end_matches(['\n', '\r'])
+                .graphemes(true)
+                .fold((0, 0), |(_, column), g| (column, column + visible_width(g)))
+                .0
+        };
+        let last = lines.iter().map(|l| last_column(l)).max().unwrap_or(0);
+        let position = |index: usize, last: usize| {
+            if last == 0 { 0.0 } else { index as f64 / last as f64 }
+        };
+
+        let mut runs: Vec<(&str, Option<Color>)> = vec![];
+        let mut run_start = 0;
+        let mut offset = 0;
+        for (line_index, line) in lines.iter().enumerate() {
+            let mut column = 0;
+            for (start, grapheme) in line.grapheme_indices(true) {
+                let start = offset + start;
+                if grapheme == "\n" || grapheme == "\r\n" {
+                    continue;
+                }
+                let t = match self.direction {
+                    Direction::Horizontal => position(column, last),
+                    Direction::Vertical => position(line_index, lines.len() - 1),
+                };
+                column += visible_width(grapheme);
+                let color = self.at(t).and_then(|c| Color::Rgb(c).downsample(level));
+                match runs.last_mut() {
+                    None => runs.push(("", color)),
+                    Some((_, c)) if *c == color => {}
+                    Some(last) => {
+                        last.0 = &text[run_start..start];
+                        runs.push(("", color));
+                        run_start = start;
+                    }
+                }
+            }
+            offset += line.len();
+        }
+        match runs.last_mut() {
+            Some(last) => {
+                last.0 = &text[run_start..];
+            }
+            None if !text.is_empty() => runs.push((text, None)),
+            None => {}
+        }
+        runs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styles::palette::PaletteColor;
+
+    const RED: Rgb = Rgb { r: 255, g: 0, b: 0 };
+    const BLUE: Rgb = Rgb { r: 0, g: 0, b: 255 };
+
+    fn rgb(r: u8, g: u8, b: u8) -> Option<Color> {
+        Some(Color::Rgb(Rgb { r, g, b }))
+    }
+
+    #[test]
+    fn colors_at_positions() {
+        let fade = Gradient::new(&[RED, Rgb { r: 0, g: 255, b: 0 }, BLUE]);
+        assert_eq!(fade.at(0.0), Some(RED));
+        assert_eq!(fade.at(0.25), Some(Rgb { r: 128, g: 128, b: 0 }));
+        assert_eq!(fade.at(1.0), Some(BLUE));
+        assert_eq!(fade.at(7.0), Some(BLUE));
+        assert_eq!(Gradient::new(&[RED]).at(0.5), Some(RED));
+        assert_eq!(Gradient::new(&[]).at(0.5), None);
+    }
+
+    #[test]
+    fn colors_in_color_spaces() {
+        let mut fade = Gradient::new(&[RED, BLUE]);
+        assert_eq!(fade.at(0.5), Some(Rgb { r: 128, g: 0, b: 128 }));
+        fade.space(ColorSpace::LinearRgb);
+        assert_eq!(fade.at(0.5), Some(Rgb { r: 188, g: 0, b: 188 }));
+        fade.space(ColorSpace::Hsl);
+        assert_eq!(fade.at(0.5), Some(Rgb { r: 255, g: 0, b: 255 }));
+        fade.space(ColorSpace::Oklab);
+        assert_eq!(fade.at(0.5), Some(Rgb { r: 140, g: 83, b: 162 }));
+    }
+
+    #[test]
+    fn hsl_takes_the_short_way() {
+        let mut fade = Gradient::new(&[RED, Rgb { r: 255, g: 0, b: 255 }]);
+        fade.space(ColorSpace::Hsl);
+        assert_eq!(fade.at(0.5), Some(Rgb { r: 255, g: 0, b: 128 }));
+    }
+
+    #[test]
+    fn horizontal_runs() {
+        let fade = Gradient::new(&[RED, BLUE]);
+        let runs = fade.runs("abc", ColorLevel::TrueColor);
+        assert_eq!(runs, vec![("a", rgb(255, 0, 0)), ("b", rgb(128, 0, 128)), ("c", rgb(0, 0, 255))]);
+
+        let runs = fade.runs("ab\nabc\n", ColorLevel::TrueColor);
+        assert_eq!(runs, vec![
+            ("a", rgb(255, 0, 0)),
+            ("b\n", rgb(128, 0, 128)),
+            ("a", rgb(255, 0, 0)),
+            ("b", rgb(128, 0, 128)),
+            ("c\n", rgb(0, 0, 255)),
+        ]);
+    }
+
+    #[test]
+    fn vertical_runs() {
+        let mut fade = Gradient::new(&[RED, BLUE]);
+        fade.direction(Direction::Vertical);
+        let runs = fade.runs("ab\ncd\r\nef", ColorLevel::TrueColor);
+        assert_eq!(runs, vec![("ab\n", rgb(255, 0, 0)), ("cd\r\n", rgb(128, 0, 128)), ("ef", rgb(0, 0, 255))]);
+    }
+
+    #[test]
+    fn runs_merge_same_colors() {
+        let fade = Gradient::new(&[RED, Rgb { r: 255, g: 10, b: 0 }]);
+        let runs = fade.runs("abcdef", ColorLevel::Ansi256);
+        assert_eq!(runs, vec![("abcdef", Some(Color::Palette(PaletteColor { index: 196 })))]);
+        assert_eq!(fade.runs("abc", ColorLevel::NoColor), vec![("abc", None)]);
+        assert_eq!(Gradient::new(&[]).runs("abc", ColorLevel::TrueColor), vec![("abc", None)]);
+        assert!(fade.runs("", ColorLevel::TrueColor).is_empty());
+        assert_eq!(fade.runs("\nab", ColorLevel::TrueColor)[0].0, "\na");
+    }
+
+    #[test]
+    fn runs_per_grapheme() {
+        let fade = Gradient::new(&[RED, BLUE]);
+        let runs = fade.runs("e\u{301}日", ColorLevel::TrueColor);
+        assert_eq!(runs, vec![("e\u{301}", rgb(255, 0, 0)), ("日", rgb(0, 0, 255))]);
+    }
+
+    #[test]
+    fn runs_follow_columns() {
+        let fade = Gradient::new(&[RED, BLUE]);
+        let runs = fade.runs("abc\n日d", ColorLevel::TrueColor);
+        assert_eq!(runs, vec![
+            ("a", rgb(255, 0, 0)),
+            ("b", rgb(128, 0, 128)),
+            ("c\n", rgb(0, 0, 255)),
+            ("日", rgb(255, 0, 0)),
+            ("d", rgb(0, 0, 255)),
+        ]);
+    }
+
+    #[test]
+    fn rainbow_presets() {
+        let rainbow = Gradient::rainbow();
+        assert_eq!(rainbow.at(0.0), Some(RED));
+        assert_eq!(rainbow.at(0.4), Some(Rgb { r: 0, g: 255, b: 0 }));
+        assert_eq!(rainbow.at(1.0), Some(Rgb { r: 255, g: 0, b: 255 }));
+        assert_eq!(Gradient::pastel_rainbow().at(0.0), Some(Rgb { r: 250, g: 158, b: 158 }));
+    }
+}
@@ -0,0 +1,626 @@
+//! A module for rendering styled text as HTML.
+//!
+//! This module provides a struct `HtmlRenderer` that turns a `StyledText` or a `StyledDocument`
+//! into `<span>` elements, styled with inline `style` attributes or with CSS classes.
+//! Basic and palette colors are turned into rgb colors with a `ColorScheme`,
+//! in class mode `stylesheet` writes the rules for every class.
+//!
+//! `HtmlRenderer::convert` turns any painted output, like a captured build log, into HTML.
+//! It reads the input in chunks, so the input can be of any size.
+
+// =======================================================================
+
+use std::io::{ self, Read, Write };
+
+use crate::{
+    ansi_code::parser::{ incomplete_escape, AnsiParser },
+    backend::HtmlBackend,
+    styles::{
+        color::Color,
+        color_scheme::ColorScheme,
+        formatter::Formatter,
+        palette::PaletteColor,
+        span_style::SpanStyle,
+        underline_style::UnderlineStyle,
+    },
+    StyledDocument,
+    StyledText,
+};
+
+// =======================================================================
+
+/// The formatters that map to one CSS class each, with the class name and the declarations.
+///
+/// Underlines, overline and strikethrough share `text-decoration` and are handled apart.
+/// `{p}` is replaced with the class prefix.
+const FORMATTER_CSS: [(u8, &str, &str); 10] = [
+    (1, "bold", "font-weight:bold"),
+    (2, "faint", "opacity:0.7"),
+    (3, "italic", "font-style:italic"),
+    (5, "slow-blink", "animation:{p}blink 1s step-end infinite"),
+    (6, "rapid-blink", "animation:{p}blink 0.5s step-end infinite"),
+    (8, "conceal", "visibility:hidden"),
+    (51, "framed", "border:1px solid"),
+    (52, "encircled", "border:1px solid;border-radius:0.6em"),
+    (73, "superscript", "vertical-align:super;font-size:smaller"),
+    (74, "subscript", "vertical-align:sub;font-size:smaller"),
+];
+
+/// The text decoration styles with their class name.
+const DECORATION_STYLES: [&str; 4] = ["double", "wavy", "dotted", "dashed"];
+
+/// An enum representing how the styles are written into the HTML.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CssMode {
+    /// Every span gets a `style` attribute with its declarations.
+    #[default]
+    Inline,
+    /// Every span gets a `class` attribute, only rgb colors are written inline.
+    Classes,
+}
+
+/// The size of the chunks `convert` reads.
+const CHUNK_SIZE: usize = 8192;
+
+/// The most bytes of an unfinished escape sequence `convert` holds back for the next chunk.
+const MAX_ESCAPE_LENGTH: usize = 4096;
+
+/// The url schemes that are written as links, other links are written as plain text.
+const LINK_SCHEMES: [&str; 5] = ["http://", "https://", "mailto:", "ftp://", "file://"];
+
+/// A struct representing the classes and declarations of a span.
+#[derive(Default)]
+struct Css {
+    classes: Vec<String>,
+    declarations: Vec<String>,
+}
+
+/// A struct for rendering styled text as HTML.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ html::HtmlRenderer, styled };
+/// let html = HtmlRenderer::new().render(styled("a < b").red().bold());
+/// assert_eq!(html, "<span style=\"color:#cd0000;font-weight:bold\">a &lt; b</span>");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlRenderer {
+    css_mode: CssMode,
+    class_prefix: String,
+    scheme: ColorScheme,
+}
+
+impl Default for HtmlRenderer {
+    fn default() -> Self {
+        HtmlRenderer::new()
+    }
+}
+
+impl HtmlRenderer {
+    /// Returns a renderer with inline styles, the class prefix `term-` and the xterm color scheme.
+    pub fn new() -> Self {
+        HtmlRenderer {
+            css_mode: CssMode::Inline,
+            class_prefix: "term-".to_string(),
+            scheme: ColorScheme::xterm(),
+        }
+    }
+
+    /// Sets whether the styles are written as inline `style` attributes or as CSS classes.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::{ html::{ CssMode, HtmlRenderer }, styled };
+    /// let html = HtmlRenderer::new()
+    ///     .css_mode(CssMode::Classes)
+    ///     .render(styled("ok").green().fg().rgb(0, 0, 128).bg().underline());
+    /// assert_eq!(
+    ///     html,
+    ///     "<span class=\"term-fg-2 term-underline\" style=\"background-color:#000080\">ok</span>"
+    /// );
+    /// ```
+    /// **rgb colors are always written inline, the rules of the classes come from `stylesheet`**
+    pub fn css_mode(&mut self, mode: CssMode) -> &mut Self {
+        self.css_mode = mode;
+        self
+    }
+
+    /// Sets the prefix of the class names and of the blink animation, the default is `term-`.
+    pub fn class_prefix(&mut self, prefix: &str) -> &mut Self {
+        self.class_prefix = prefix.to_string();
+        self
+    }
+
+    /// Sets the color scheme that basic and palette colors are shown with.
+    pub fn color_scheme(&mut self, scheme: ColorScheme) -> &mut Self {
+        self.scheme = scheme;
+        self
+    }
+
+    /// Renders a styled text, the color level of the text is ignored.
+    ///
+    /// A link of the text becomes an `<a>` element if it has one of the schemes `convert` allows.
+    pub fn render(&self, styled: &StyledText) -> String {
+        let mut doc = StyledDocument::new();
+        doc.push(styled);
+        self.render_document(&doc)
+    }
+
+    /// Renders a document, pieces of text with the same style share one span.
+    ///
+    /// Text without any style is written without a span.
+    pub fn render_document(&self, doc: &StyledDocument) -> String {
+        let mut backend = HtmlBackend::new(self.clone());
+        doc.render(&mut backend);
+        backend.output().to_string()
+    }
+
+    /// Returns the opening `<span>` tag of a style, or `None` if the style needs no span.
+    pub(crate) fn open_tag(&self, style: &SpanStyle) -> Option<String> {
+        let css = self.css(style);
+        if css.classes.is_empty() && css.declarations.is_empty() {
+            return None;
+        }
+        let mut tag = "<span".to_string();
+        if !css.classes.is_empty() {
+            tag.push_str(&format!(" class=\"{}\"", css.classes.join(" ")));
+        }
+        if !css.declarations.is_empty() {
+            tag.push_str(&format!(" style=\"{}\"", css.declarations.join(";")));
+        }
+        tag.push('>');
+        Some(tag)
+    }
+
+    /// Returns the classes and declarations of a style.
+    ///
+    /// Inverse swaps the foreground and background color, a missing color is swapped
+    /// with the default color of the scheme.
+    fn css(&self, style: &SpanStyle) -> Css {
+        let mut css = Css::default();
+        let inverse = style.formatters.contains(&Formatter { code: 7 });
+        let (fg, bg) = match inverse {
+            true => (style.bg, style.fg),
+            false => (style.fg, style.bg),
+        };
+        match (fg, inverse) {
+            (Some(c), _) => self.add_color(&mut css, "fg", "color", &c),
+            (None, true) => {
+                let declaration = format!("color:{}", self.scheme.background.to_hex());
+                self.add(&mut css, "inverse-fg", &declaration);
+            }
+            (None, false) => {}
+        }
+        match (bg, inverse) {
+            (Some(c), _) => self.add_color(&mut css, "bg", "background-color", &c),
+            (None, true) => {
+                let declaration = format!("background-color:{}", self.scheme.foreground.to_hex());
+                self.add(&mut css, "inverse-bg", &declaration);
+            }
+            (None, false) => {}
+        }
+
+        for (code, class, declaration) in FORMATTER_CSS {
+            if style.formatters.contains(&Formatter { code }) {
+                self.add(&mut css, class, &declaration.replace("{p}", &self.class_prefix));
+            }
+        }
+
+        let has = |code: u8| style.formatters.contains(&Formatter { code });
+        let mut lines = vec![];
+        if has(4) || has(21) || style.underline_style.is_some() {
+            lines.push("underline");
+        }
+        if has(53) {
+            lines.push("overline");
+        }
+        if has(9) {
+            lines.push("line-through");
+        }
+        if !lines.is_empty() {
+            let class = lines.join("-").replace("line-through", "strikethrough");
+            self.add(&mut css, &class, &format!("text-decoration-line:{}", lines.join(" ")));
+        }
+        let decoration_style = match style.underline_style {
+            Some(UnderlineStyle::Curly) => Some("wavy"),
+            Some(UnderlineStyle::Dotted) => Some("dotted"),
+            Some(UnderlineStyle::Dashed) => Some("dashed"),
+            None if has(21) => Some("double"),
+            None => None,
+        };
+        if let Some(decoration_style) = decoration_style {
+            self.add(&mut css, decoration_style, &format!("text-decoration-style:{}", decoration_style));
+        }
+        if let Some(c) = &style.ul {
+            self.add_color(&mut css, "ul", "text-decoration-color", c);
+        }
+        css
+    }
+
+    /// Adds a class in class mode or its declaration in inline mode.
+    fn add(&self, css: &mut Css, class: &str, declaration: &str) {
+        match self.css_mode {
+            CssMode::Inline => css.declarations.push(declaration.to_string()),
+            CssMode::Classes => css.classes.push(format!("{}{}", self.class_prefix, class)),
+        }
+    }
+
+    /// Adds a color, basic and palette colors get a class like `fg-1` in class mode.
+    fn add_color(&self, css: &mut Css, kind: &str, property: &str, color: &Color) {
+        let index = match color {
+            Color::Basic(c) => c.index(),
+            Color::Palette(c) => Some(c.index),
+            Color::Rgb(_) => None,
+        };
+        let declaration = format!("{}:{}", property, self.scheme.resolve(color).to_hex());
+        match index {
+            Some(index) if self.css_mode == CssMode::Classes => {
+                css.classes.push(format!("{}{}-{}", self.class_prefix, kind, index));
+            }
+            _ => css.declarations.push(declaration),
+        }
+    }
+
+    /// Converts painted text into HTML.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::html::HtmlRenderer;
+    /// let html = HtmlRenderer::new().convert_str("\x1b[1mok\x1b[0m <done>");
+    /// assert_eq!(html, "<span style=\"font-weight:bold\">ok</span> &lt;done&gt;");
+    /// ```
+    /// see `convert` for how escape sequences are turned into HTML.
+    pub fn convert_str(&self, painted: &str) -> String {
+        let mut html = String::new();
+        let mut converter = Converter::new(self);
+        converter.push(&mut html, painted);
+        converter.finish(&mut html);
+        html
+    }
+
+    /// Converts painted text from a reader into HTML and writes it into a writer.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::html::HtmlRenderer;
+    /// let log = "\x1b[31mFAILED\n\x1b[1mtests/a.rs\x1b[0m see \x1b]8;;https://ci.example\x1b\\logs\x1b]8;;\x1b\\";
+    /// let mut html = Vec::new();
+    /// HtmlRenderer::new().convert(log.as_bytes(), &mut html)?;
+    /// assert_eq!(
+    ///     String::from_utf8(html).unwrap(),
+    ///     "<span style=\"color:#cd0000\">FAILED\n</span>\
+    ///      <span style=\"color:#cd0000;font-weight:bold\">tests/a.rs</span> see \
+    ///      <a href=\"https://ci.example\">logs</a>"
+    /// );
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    /// The input is read in chunks and the style is kept from one line to the next.
+    /// SGR sequences set the style of the spans, OSC 8 hyperlinks become `<a>` elements
+    /// and every other escape sequence is removed. Only links with the `http`, `https`, `mailto`,
+    /// `ftp` or `file` scheme become elements, other links are written as plain text.
+    /// An escape sequence that is still not terminated after 4 KiB is dropped,
+    /// so the output keeps streaming.
+    /// Invalid UTF-8 is replaced with `U+FFFD`.
+    pub fn convert<R: Read, W: Write>(&self, mut reader: R, mut writer: W) -> io::Result<()> {
+        let mut converter = Converter::new(self);
+        let mut buffer = [0; CHUNK_SIZE];
+        let mut pending: Vec<u8> = vec![];
+        loop {
+            let read = match reader.read(&mut buffer) {
+                Ok(read) => read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {
+                    continue;
+                }
+                Err(err) => {
+                    return Err(err);
+                }
+            };
+            pending.extend_from_slice(&buffer[..read]);
+            // at the end of the input everything is converted, even an incomplete sequence
+            let utf8_end = if read == 0 { pending.len() } else { complete_utf8(&pending) };
+            let text = String::from_utf8_lossy(&pending[..utf8_end]).into_owned();
+            let mut text_end = match read {
+                0 => text.len(),
+                _ => incomplete_escape(&text).unwrap_or(text.len()),
+            };
+            let mut html = String::new();
+            converter.push(&mut html, &text[..text_end]);
+            // a sequence this long is most likely never terminated, so it is dropped instead of
+            // held back until the end, this also keeps what is scanned again per chunk small
+            if text.len() - text_end > MAX_ESCAPE_LENGTH {
+                text_end = text.len();
+            }
+            if read == 0 {
+                converter.finish(&mut html);
+            }
+            writer.write_all(html.as_bytes())?;
+            if read == 0 {
+                return Ok(());
+            }
+            let mut rest = text.as_bytes()[text_end..].to_vec();
+            rest.extend_from_slice(&pending[utf8_end..]);
+            pending = rest;
+        }
+    }
+
+    /// Returns the CSS rules of every class and the keyframes of the blink animation.
+    ///
+    /// Blinking text needs the keyframes in inline mode too.
+    pub fn stylesheet(&self) -> String {
+        let p = &self.class_prefix;
+        let mut css = format!("@keyframes {}blink {{ 50% {{ visibility: hidden; }} }}\n", p);
+        for (_, class, declaration) in FORMATTER_CSS {
+            css.push_str(&format!(".{}{} {{ {}; }}\n", p, class, declaration.replace("{p}", p)));
+        }
+        for lines in 1..8 {
+            let names: Vec<&str> = ["underline", "overline", "line-through"]
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| lines & (1 << i) != 0)
+                .map(|(_, name)| *name)
+                .collect();
+            let class = names.join("-").replace("line-through", "strikethrough");
+            css.push_str(&format!(".{}{} {{ text-decoration-line: {}; }}\n", p, class, names.join(" ")));
+        }
+        for decoration_style in DECORATION_STYLES {
+            css.push_str(&format!(".{}{} {{ text-decoration-style: {}; }}\n", p, decoration_style, decoration_style));
+        }
+        css.push_str(&format!(".{}inverse-fg {{ color: {}; }}\n", p, self.scheme.background.to_hex()));
+        css.push_str(&format!(".{}inverse-bg {{ background-color: {}; }}\n", p, self.scheme.foreground.to_hex()));
+        for index in 0..=255 {
+            let hex = self.scheme.resolve(&Color::Palette(PaletteColor { index })).to_hex();
+            css.push_str(&format!(".{}fg-{} {{ color: {}; }}\n", p, index, hex));
+            css.push_str(&format!(".{}bg-{} {{ background-color: {}; }}\n", p, index, hex));
+            css.push_str(&format!(".{}ul-{} {{ text-decoration-color: {}; }}\n", p, index, hex));
+        }
+        css
+    }
+}
+
+/// A struct for converting painted text into HTML piece by piece.
+///
+/// The parser keeps the style and the link between the pieces, and the span and link
+/// of the last piece are kept open, so the HTML does not depend on where the input is split.
+struct Converter<'a> {
+    renderer: &'a HtmlRenderer,
+    parser: AnsiParser,
+    link: Option<String>,
+    style: SpanStyle,
+    span_open: bool,
+}
+
+impl<'a> Converter<'a> {
+    /// Returns a converter that starts with the default style of the terminal.
+    fn new(renderer: &'a HtmlRenderer) -> Self {
+        Converter { renderer, parser: AnsiParser::new(), link: None, style: SpanStyle::default(), span_open: false }
+    }
+
+    /// Converts a piece of painted text, which must not end in the middle of an escape sequence.
+    fn push(&mut self, html: &mut String, painted: &str) {
+        for span in self.parser.parse(painted) {
+            let link = span.link.filter(|url| has_link_scheme(url));
+            if link != self.link || span.style != self.style {
+                self.close_span(html);
+                self.style = span.style;
+            }
+            if link != self.link {
+                if self.link.is_some() {
+                    html.push_str("</a>");
+                }
+                if let Some(url) = &link {
+                    html.push_str(&format!("<a href=\"{}\">", escape_html(url)));
+                }
+                self.link = link;
+            }
+            if !self.span_open {
+                if let Some(tag) = self.renderer.open_tag(&self.style) {
+                    html.push_str(&tag);
+                    self.span_open = true;
+                }
+            }
+            html.push_str(&escape_html(&span.text));
+        }
+    }
+
+    /// Closes the span that is still open.
+    fn close_span(&mut self, html: &mut String) {
+        if self.span_open {
+            html.push_str("</span>");
+            self.span_open = false;
+        }
+    }
+
+    /// Closes the span and the link that are still open.
+    fn finish(&mut self, html: &mut String) {
+        self.close_span(html);
+        if self.link.take().is_some() {
+            html.push_str("</a>");
+        }
+    }
+}
+
+/// Returns `true` if the url has one of the schemes that are written as links.
+pub(crate) fn has_link_scheme(url: &str) -> bool {
+    LINK_SCHEMES.iter().any(|s| url.starts_with(s))
+}
+
+/// Returns the length of the bytes without an incomplete UTF-8 character at their end.
+fn complete_utf8(bytes: &[u8]) -> usize {
+    for back in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - back];
+        if byte & 0xc0 != 0x80 {
+            let width = match byte {
+                0xf0.. => 4,
+                0xe0.. => 3,
+                0xc0.. => 2,
+                _ => 1,
+            };
+            return if width > back { bytes.len() - back } else { bytes.len() };
+        }
+    }
+    bytes.len()
+}
+
+/// Escapes the characters that have a meaning in HTML: `&`, `<`, `>`, `"` and `'`.
+///
+/// # Example:
+/// ```
+/// use term_tools::html::escape_html;
+/// assert_eq!(escape_html("<a href=\"x\">&</a>"), "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;");
+/// ```
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ styled, styles::rgb::Rgb };
+
+    #[test]
+    fn render_plain_text() {
+        assert_eq!(HtmlRenderer::new().render(&styled("'a' & b")), "&#39;a&#39; &amp; b");
+    }
+
+    #[test]
+    fn render_inline_styles() {
+        let html = HtmlRenderer::new().render(styled("a").palette(208).italic().overline().strikethrough());
+        assert_eq!(
+            html,
+            "<span style=\"color:#ff8700;font-style:italic;text-decoration-line:overline line-through\">a</span>"
+        );
+        let html = HtmlRenderer::new().render(styled("a").curly_underline().blue().ul().slow_blink());
+        assert_eq!(
+            html,
+            "<span style=\"animation:term-blink 1s step-end infinite;text-decoration-line:underline;\
+             text-decoration-style:wavy;text-decoration-color:#0000ee\">a</span>"
+        );
+    }
+
+    #[test]
+    fn render_classes() {
+        let html = HtmlRenderer::new()
+            .css_mode(CssMode::Classes)
+            .class_prefix("t-")
+            .render(styled("a").bright_red().fg().bold().double_underline().black().bg());
+        assert_eq!(html, "<span class=\"t-fg-9 t-bg-0 t-bold t-underline t-double\">a</span>");
+    }
+
+    #[test]
+    fn render_inverse() {
+        let html = HtmlRenderer::new().render(styled("a").red().inverse());
+        assert_eq!(html, "<span style=\"color:#000000;background-color:#cd0000\">a</span>");
+        let html = HtmlRenderer::new().css_mode(CssMode::Classes).render(styled("a").inverse());
+        assert_eq!(html, "<span class=\"term-inverse-fg term-inverse-bg\">a</span>");
+    }
+
+    #[test]
+    fn render_with_color_scheme() {
+        let mut scheme = ColorScheme::xterm_light();
+        scheme.basic[1] = Rgb { r: 220, g: 50, b: 47 };
+        let html = HtmlRenderer::new().color_scheme(scheme).render(styled("a").red());
+        assert_eq!(html, "<span style=\"color:#dc322f\">a</span>");
+    }
+
+    #[test]
+    fn render_document_merges_spans() {
+        let mut doc = StyledDocument::new();
+        doc.push(styled("a").red()).push(styled("b").red()).text("c");
+        let html = HtmlRenderer::new().render_document(&doc);
+        assert_eq!(html, "<span style=\"color:#cd0000\">ab</span>c");
+    }
+
+    #[test]
+    fn stylesheet_has_every_class() {
+        let css = HtmlRenderer::new().stylesheet();
+        assert!(css.starts_with("@keyframes term-blink"));
+        assert!(css.contains(".term-fg-196 { color: #ff0000; }"));
+        assert!(css.contains(".term-underline-overline-strikethrough { text-decoration-line: underline overline line-through; }"));
+        assert!(css.contains(".term-wavy { text-decoration-style: wavy; }"));
+        assert!(css.contains(".term-slow-blink { animation:term-blink 1s step-end infinite; }"));
+    }
+
+    /// A reader that returns one byte per read, to split every escape sequence and character.
+    struct ByteReader<'a>(&'a [u8]);
+
+    impl Read for ByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.0.split_first() {
+                Some((first, rest)) if !buf.is_empty() => {
+                    buf[0] = *first;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn convert_in_small_chunks() {
+        let log = "\x1b[38;2;255;0;0mré\x1b[1m\u{1f600}\n\x1b]8;;http://x\x07l\x1b]8;;\x07\x1b[0mend";
+        let mut html = Vec::new();
+        HtmlRenderer::new().convert(ByteReader(log.as_bytes()), &mut html).unwrap();
+        assert_eq!(String::from_utf8(html).unwrap(), HtmlRenderer::new().convert_str(log));
+        assert_eq!(
+            HtmlRenderer::new().convert_str(log),
+            "<span style=\"color:#ff0000\">ré</span><span style=\"color:#ff0000;font-weight:bold\">\u{1f600}\n</span>\
+             <a href=\"http://x\"><span style=\"color:#ff0000;font-weight:bold\">l</span></a>end"
+        );
+    }
+
+    #[test]
+    fn convert_streams_past_unterminated_sequences() {
+        // fails after the input, so everything written came before the end
+        struct Unfinished(Vec<u8>);
+        impl Read for Unfinished {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                if self.0.is_empty() {
+                    return Err(io::Error::other("not finished"));
+                }
+                let n = buf.len().min(self.0.len());
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0.drain(..n);
+                Ok(n)
+            }
+        }
+        let input = format!("a\x1b]0;title{}", "x".repeat(3 * CHUNK_SIZE));
+        let mut html = Vec::new();
+        assert!(HtmlRenderer::new().convert(Unfinished(input.into_bytes()), &mut html).is_err());
+        let html = String::from_utf8(html).unwrap();
+        assert!(html.starts_with("ax"));
+        assert!(html.len() > 2 * CHUNK_SIZE);
+    }
+
+    #[test]
+    fn convert_keeps_style_across_lines() {
+        let html = HtmlRenderer::new().convert_str("\x1b[7ma\nb\x1b[27m\nc");
+        assert_eq!(html, "<span style=\"color:#000000;background-color:#e5e5e5\">a\nb</span>\nc");
+    }
+
+    #[test]
+    fn convert_unsafe_links_as_text() {
+        let html = HtmlRenderer::new().convert_str("\x1b]8;;javascript:alert(1)\x07x\x1b]8;;\x07");
+        assert_eq!(html, "x");
+        let html = HtmlRenderer::new().convert_str("\x1b]8;;https://a.b/?q=\"<\x07x");
+        assert_eq!(html, "<a href=\"https://a.b/?q=&quot;&lt;\">x</a>");
+    }
+
+    #[test]
+    fn convert_invalid_utf8() {
+        let mut html = Vec::new();
+        HtmlRenderer::new().convert(&b"a\xffb\xe2\x82"[..], &mut html).unwrap();
+        assert_eq!(String::from_utf8(html).unwrap(), "a\u{fffd}b\u{fffd}");
+    }
+}
@@ -3,19 +3,52 @@
 //! term_tools is a Rust library that provides a rich API for colorizing terminal output.
 //! It allows you to create styled text strings with various colors, effects, and formatters.
 
-mod ansi_code;
-mod styles;
+pub mod ansi_code;
+pub mod styles;
+pub mod detect;
+pub mod width;
+pub mod document;
+pub mod style;
+pub mod gradient;
+pub mod markup;
+pub mod styled_format;
+pub mod theme;
+pub mod html;
+pub mod svg;
+pub mod backend;
+pub mod table;
+#[cfg(any(test, feature = "test-support"))]
+pub mod testing;
+
+pub use document::StyledDocument;
+pub use style::Style;
+pub use theme::Theme;
+pub use styles::{
+    color_level::ColorLevel,
+    color_spec::ParseColorError,
+    reset_mode::ResetMode,
+    sgr_mode::SgrMode,
+};
+pub use width::{ strip_ansi, visible_width };
 
 // =======================================================================
+use std::{ fmt, io };
+
 use crate::{
-    ansi_code::ANSIEscapeCode,
+    ansi_code::hyperlink::Hyperlink,
+    gradient::Gradient,
     styles::{
         basic_color,
+        color::Color,
         formatter,
         paint_type::PaintType,
         palette::PaletteColor,
         rgb::Rgb,
+        color_spec,
+        span_style::SpanStyle,
+        underline_style,
         Styles,
+        Stylify,
     },
 };
 
@@ -73,9 +106,16 @@ pub fn styled(text: &str) -> StyledText {
 }
 
 /// A struct representing a styled text string.
+#[derive(Debug, Clone)]
 pub struct StyledText {
     text: String,
     start_styles: Vec<Styles>,
+    color_level: ColorLevel,
+    reset_mode: ResetMode,
+    sgr_mode: SgrMode,
+    gradient: Option<Gradient>,
+    link: Option<Hyperlink>,
+    hyperlinks: bool,
 }
 
 impl StyledText {
@@ -84,36 +124,383 @@ impl StyledText {
         StyledText {
             text,
             start_styles: vec![],
+            color_level: ColorLevel::default(),
+            reset_mode: ResetMode::default(),
+            sgr_mode: SgrMode::default(),
+            gradient: None,
+            link: None,
+            hyperlinks: true,
         }
     }
 
     /// Paints the styled text string with the given styles.
     ///
     /// This method returns a string representing the styled text.
+    /// The colors are downsampled to the color level of the styled text,
+    /// see [`StyledText::color_level`].
+    /// It is the same as `to_string`, use `write!` or [`StyledText::write_to`]
+    /// to paint without allocating a new string.
     pub fn paint(&mut self) -> String {
-        let mut default_paint_type = PaintType::FG;
+        self.to_string()
+    }
+
+    /// Writes the painted text into an `io::Write`, like a file or the standard output.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let mut out = Vec::new();
+    /// styled("Well begun is half done.").green().write_to(&mut out).unwrap();
+    /// assert_eq!(out, b"\x1b[32mWell begun is half done.\x1b[0m");
+    /// ```
+    /// the escape codes and the text are written straight into the writer.
+    pub fn write_to<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(writer, "{}", self)
+    }
+
+    /// Renders the text through a backend instead of painting escape codes,
+    /// see the [`backend`] module.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::{ backend::RecordingBackend, styled };
+    /// let mut backend = RecordingBackend::new();
+    /// styled("Haste makes waste.").yellow().render(&mut backend);
+    /// assert_eq!(backend.text(), "Haste makes waste.");
+    /// ```
+    /// the backend receives the styles, escape codes and link that `paint` writes,
+    /// with every setting of the text like the reset mode and the SGR mode.
+    pub fn render<B: backend::Backend + ?Sized>(&self, backend: &mut B) {
+        let link = self.link.as_ref().filter(|_| self.hyperlinks && self.color_level != ColorLevel::NoColor);
+        if link.is_some() {
+            backend.set_link(link);
+        }
+        if self.color_level == ColorLevel::NoColor {
+            backend.write_text(&self.text);
+        } else {
+            self.render_styled(backend);
+        }
+        if link.is_some() {
+            backend.set_link(None);
+        } else if let Some(link) = &self.link {
+            backend.write_text(" (");
+            backend.write_text(link.encoded_url());
+            backend.write_text(")");
+        }
+        backend.end();
+    }
+
+    /// Renders the styles and the text, without the link.
+    fn render_styled<B: backend::Backend + ?Sized>(&self, backend: &mut B) {
+        let mut style = SpanStyle::default();
+        for (s, p) in self.start_params() {
+            style.apply(&s, &p);
+        }
+        let gradient_runs = self.gradient.as_ref().map(|g| g.runs(&self.text, self.color_level));
+        let plain = [(self.text.as_str(), None)];
+        let runs = match &gradient_runs {
+            Some(runs) if !runs.is_empty() => runs.as_slice(),
+            _ => &plain,
+        };
+
+        // the first color of a gradient is written together with the start codes
+        let mut previous = SpanStyle::default();
+        let mut has_codes = false;
+        for (i, (run, color)) in runs.iter().enumerate() {
+            let color = color.filter(|c| style.fg != Some(*c));
+            if color.is_some() {
+                style.fg = color;
+            }
+            let codes = SgrCodes { text: self, start: i == 0, color, reset: None };
+            has_codes |= !codes.is_empty();
+            codes.set_style(backend, &previous, &style);
+            previous = style;
+            if !run.is_empty() {
+                backend.write_text(run);
+            }
+        }
+
+        let reset = match self.reset_mode {
+            ResetMode::Full if self.sgr_mode == SgrMode::Combined && !has_codes => None,
+            _ => Some(style),
+        };
+        let codes = SgrCodes { text: self, start: false, color: None, reset };
+        codes.set_style(backend, &style, &SpanStyle::default());
+    }
+
+    /// Returns the start styles that are painted, each with its paint type, downsampled to
+    /// the color level. In `SgrMode::Combined` the overridden styles are left out.
+    fn start_params(&self) -> impl Iterator<Item = (Styles, PaintType)> + '_ {
+        let combined = self.sgr_mode == SgrMode::Combined;
+        self.start_styles
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, style)| {
+                if let Styles::StylePaintType(_) = style {
+                    return None;
+                }
+                if combined && self.is_overridden(i) {
+                    return None;
+                }
+                let paint_type = self.paint_type_at(i);
+                Some((style.downsample_as(self.color_level, &paint_type)?, paint_type))
+            })
+    }
+
+    /// Returns the paint type of the style at the given index.
+    ///
+    /// The first paint type style after the index applies, if there is none it is `FG`.
+    fn paint_type_at(&self, index: usize) -> PaintType {
+        self.start_styles[index + 1..]
+            .iter()
+            .find_map(|s| {
+                match s {
+                    Styles::StylePaintType(p) => Some(*p),
+                    _ => None,
+                }
+            })
+            .unwrap_or(PaintType::FG)
+    }
+
+    /// Returns `true` if a later style overrides the style at the given index,
+    /// so the style has no effect on the text.
+    ///
+    /// Everything before a `reset` and the `reset` itself is overridden, as is a color
+    /// followed by another color of the same paint type, a formatter followed by the
+    /// same formatter or its off code and an underline followed by another underline.
+    /// With a gradient that has stops every foreground color is overridden by its colors.
+    fn is_overridden(&self, index: usize) -> bool {
+        let later = &self.start_styles[index + 1..];
+        if later.contains(&formatter::RESET) {
+            return true;
+        }
+        match &self.start_styles[index] {
+            Styles::StylePaintType(_) => true,
+            Styles::StyleFormatter(f) if f.code == 0 => true,
+            Styles::StyleFormatter(f) => {
+                later.iter().any(|s| {
+                    match s {
+                        Styles::StyleFormatter(l) => l.code == f.code || f.off_code() == Some(l.code),
+                        Styles::StyleUnderline(_) => matches!(f.code, 4 | 21),
+                        _ => false,
+                    }
+                })
+            }
+            Styles::StyleUnderline(_) => {
+                later.iter().any(|s| {
+                    match s {
+                        Styles::StyleFormatter(l) => underline_style::is_underline_formatter(l),
+                        Styles::StyleUnderline(_) => true,
+                        _ => false,
+                    }
+                })
+            }
+            _ => {
+                let paint_type = self.paint_type_at(index);
+                let has_gradient = self.gradient.as_ref().is_some_and(|g| g.at(0.0).is_some());
+                if has_gradient && paint_type == PaintType::FG {
+                    return true;
+                }
+                later
+                    .iter()
+                    .enumerate()
+                    .any(|(j, s)| {
+                        Color::from_styles(s).is_some() && self.paint_type_at(index + 1 + j) == paint_type
+                    })
+            }
+        }
+    }
 
-        let start_codes_list: Vec<String> = self.start_styles
+    /// Returns the styles to paint, each with the paint type it is painted with.
+    ///
+    /// The paint type styles are removed and the colors are downsampled to the given color level.
+    pub(crate) fn resolved_styles(&self, color_level: ColorLevel) -> Vec<(Styles, PaintType)> {
+        let mut default_paint_type = PaintType::FG;
+        let mut styles: Vec<(Styles, PaintType)> = self.start_styles
             .iter()
             .rev()
             .filter_map(|s| {
                 if let Styles::StylePaintType(p) = s {
-                    default_paint_type = p.clone();
+                    default_paint_type = *p;
                     return None;
                 }
-                let t = s.make_styles(Some(&default_paint_type));
-                Some(ANSIEscapeCode::new(t.as_str()).code())
+                Some((s.downsample_as(color_level, &default_paint_type)?, default_paint_type))
             })
-            .collect::<Vec<_>>()
-            .into_iter()
-            .rev()
             .collect();
-        let start_codes = start_codes_list.join("");
-        let end_codes = ANSIEscapeCode::new(
-            &formatter::RESET.make_styles(Some(&default_paint_type))
-        ).code();
+        styles.reverse();
+        styles
+    }
+
+    /// Returns the number of terminal columns the text takes when painted.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let width = styled("你好, world").red().bold().visible_width();
+    /// assert_eq!(width, 11);
+    /// ```
+    /// escape codes in the text itself, for example from a painted text inside it, are not counted.
+    pub fn visible_width(&self) -> usize {
+        visible_width(&self.text)
+    }
+
+    /// Sets the color level that `paint` should respect.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::{ styled, ColorLevel };
+    /// let styled_text = styled("Waste no more time arguing.")
+    ///     .rgb(255, 0, 0)
+    ///     .color_level(ColorLevel::Ansi256)
+    ///     .paint();
+    /// assert_eq!(styled_text, "\x1b[38;5;196mWaste no more time arguing.\x1b[0m");
+    /// ```
+    /// `Rgb` colors are mapped to the nearest xterm 256 color on `Ansi256`
+    /// and to the nearest basic color on `Ansi16`, palette colors are mapped
+    /// to the nearest basic color on `Ansi16`.
+    /// On `NoColor` the text is painted without any escape code.
+    /// **the default level is `TrueColor`, which paints every color as it is**
+    /// use the [`detect`] module to find the level of the terminal you are writing to.
+    pub fn color_level(&mut self, level: ColorLevel) -> &mut Self {
+        self.color_level = level;
+        self
+    }
+
+    /// Sets how the styles are turned off at the end of the painted text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::{ styled, ResetMode };
+    /// let inner = styled("inner").red().reset_mode(ResetMode::Targeted).paint();
+    /// assert_eq!(inner, "\x1b[31minner\x1b[39m");
+    /// let outer = styled(&format!("outer {} still bold", inner)).bold().paint();
+    /// ```
+    /// with `ResetMode::Targeted` only the styles of this text are turned off
+    /// (`22` bold/faint, `23` italic, `24` underline, `25` blink, `55` overline,
+    /// `39` foreground and `49` background), so the styles of the text around it stay active.
+    /// **the default mode is `ResetMode::Full`, which ends the text with `\x1b[0m`**
+    pub fn reset_mode(&mut self, mode: ResetMode) -> &mut Self {
+        self.reset_mode = mode;
+        self
+    }
+
+    /// Adds the colors and formatters of a reusable `Style`.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::{ styled, Style };
+    /// const PATH: Style = Style::new().cyan().underline();
+    /// let styled_text = styled("src/lib.rs").style(&PATH).bold().paint();
+    /// ```
+    pub fn style(&mut self, style: &Style) -> &mut Self {
+        self.start_styles.extend(style.to_styles());
+        self
+    }
+
+    /// Sets how the escape codes of the painted text are written.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::{ styled, SgrMode };
+    /// let styled_text = styled("Less is more.")
+    ///     .red()
+    ///     .bold()
+    ///     .underline()
+    ///     .sgr_mode(SgrMode::Combined)
+    ///     .paint();
+    /// assert_eq!(styled_text, "\x1b[31;1;4mLess is more.\x1b[0m");
+    /// ```
+    /// with `SgrMode::Combined` all the parameters are written in one escape code
+    /// and the overridden ones are dropped, so of two foreground colors only the last one
+    /// is written and a `reset` after the other styles leaves nothing to write.
+    /// **the default mode is `SgrMode::Separate`, which writes one escape code per style**
+    pub fn sgr_mode(&mut self, mode: SgrMode) -> &mut Self {
+        self.sgr_mode = mode;
+        self
+    }
+
+    /// Paints the text with a gradient, each grapheme gets its own foreground color.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::{ styled, gradient::Gradient };
+    /// use term_tools::styles::rgb::Rgb;
+    /// let fade = Gradient::new(&[Rgb { r: 255, g: 0, b: 0 }, Rgb { r: 0, g: 0, b: 255 }]);
+    /// let styled_text = styled("abc").bold().gradient(&fade).paint();
+    /// assert_eq!(
+    ///     styled_text,
+    ///     "\x1b[1m\x1b[38;2;255;0;0ma\x1b[38;2;128;0;128mb\x1b[38;2;0;0;255mc\x1b[0m"
+    /// );
+    /// ```
+    /// graphemes next to each other with the same color share one escape code,
+    /// and the colors are downsampled to the color level like the other colors.
+    /// **the gradient paints over the foreground colors you have called**
+    pub fn gradient(&mut self, gradient: &Gradient) -> &mut Self {
+        self.gradient = Some(gradient.clone());
+        self
+    }
+
+    /// Makes the text a clickable OSC 8 hyperlink to the url.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("docs").blue().link("https://docs.rs").paint();
+    /// assert_eq!(styled_text, "\x1b]8;;https://docs.rs\x1b\\\x1b[34mdocs\x1b[0m\x1b]8;;\x1b\\");
+    /// ```
+    /// the link is written around the styles, so it works with every color and formatter.
+    /// The url is percent encoded where terminals need it, see [`Hyperlink::open`].
+    /// On terminals without hyperlinks the text is followed by the url, see [`StyledText::hyperlinks`].
+    pub fn link(&mut self, url: &str) -> &mut Self {
+        self.link = Some(Hyperlink::new(url));
+        self
+    }
 
-        format!("{}{}{}", start_codes, self.text, end_codes)
+    /// Makes the text a clickable OSC 8 hyperlink to the url with an id.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let first = styled("a long").bold().link_with_id("https://x.y", "ref-1").paint();
+    /// let second = styled("link").bold().link_with_id("https://x.y", "ref-1").paint();
+    /// assert_eq!(first, "\x1b]8;id=ref-1;https://x.y\x1b\\\x1b[1ma long\x1b[0m\x1b]8;;\x1b\\");
+    /// ```
+    /// terminals highlight all the text of links with the same id and url together,
+    /// so a link split over lines or cells can keep one id.
+    pub fn link_with_id(&mut self, url: &str, id: &str) -> &mut Self {
+        self.link = Some(Hyperlink::new(url).id(id));
+        self
+    }
+
+    /// Sets if the terminal supports hyperlinks.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("docs").bold().link("https://docs.rs").hyperlinks(false).paint();
+    /// assert_eq!(styled_text, "\x1b[1mdocs\x1b[0m (https://docs.rs)");
+    /// ```
+    /// when they are not supported, the url is written in parentheses after the text,
+    /// without the styles of the text and percent encoded like in the hyperlink,
+    /// so it can not contain control characters. It is also written like this on `ColorLevel::NoColor`.
+    /// **the default is `true`**
+    /// use [`StyledText::detect_hyperlinks`] to find out if the standard output supports them.
+    pub fn hyperlinks(&mut self, supported: bool) -> &mut Self {
+        self.hyperlinks = supported;
+        self
+    }
+
+    /// Sets if hyperlinks are supported from what [`detect::stdout_hyperlinks`] finds,
+    /// so the url is written after the text on terminals without them.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("docs").link("https://docs.rs").detect_hyperlinks().paint();
+    /// ```
+    /// for the standard error use `hyperlinks(detect::stderr_hyperlinks())`.
+    pub fn detect_hyperlinks(&mut self) -> &mut Self {
+        self.hyperlinks(detect::stdout_hyperlinks())
     }
 
     /// Sets the foreground color of the colors you have called.
@@ -148,6 +535,21 @@ impl StyledText {
         self
     }
 
+    /// Sets the underline color of the colors you have called.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("unused variable").curly_underline().rgb(255, 0, 0).ul().paint();
+    /// assert_eq!(styled_text, "\x1b[4:3m\x1b[58;2;255;0;0munused variable\x1b[0m");
+    /// ```
+    /// the colors before this method will paint as underline color, like with `fg` and `bg`.
+    /// **the underline color is dropped on `ColorLevel::Ansi16` and `ColorLevel::NoColor`**
+    pub fn ul(&mut self) -> &mut Self {
+        self.start_styles.push(Styles::StylePaintType(PaintType::UL));
+        self
+    }
+
     // Colors
 
     /// Sets the `rgb` color to the input text.
@@ -162,6 +564,61 @@ impl StyledText {
         self
     }
 
+    /// Sets the color of a hex code to the input text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("Well done is better than well said.").hex("#1e90ff")?.paint();
+    /// assert_eq!(styled_text, "\x1b[38;2;30;144;255mWell done is better than well said.\x1b[0m");
+    /// # Ok::<(), term_tools::ParseColorError>(())
+    /// ```
+    ///
+    /// the `#` is optional and both the `rgb` and the `rrggbb` forms are supported.
+    /// **if the hex code is not valid the error says why and no color is added**
+    pub fn hex(&mut self, hex: &str) -> Result<&mut Self, ParseColorError> {
+        let hex = hex.trim();
+        let rgb = color_spec::parse_hex(hex.strip_prefix('#').unwrap_or(hex))?;
+        self.start_styles.push(Styles::StyleRgb(rgb));
+        Ok(self)
+    }
+
+    /// Sets the color of a color spec to the input text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("Well done is better than well said.")
+    ///     .color("dodgerblue")?
+    ///     .color("hsl(0, 0%, 10%)")?
+    ///     .bg()
+    ///     .paint();
+    /// # Ok::<(), term_tools::ParseColorError>(())
+    /// ```
+    ///
+    /// the spec can be a hex code like `#1e90ff`, an `rgb(30, 144, 255)` or `hsl(210, 100%, 56%)`
+    /// function or one of the 148 CSS named colors like `dodgerblue`.
+    /// **if the spec is not valid the error says why and no color is added**
+    pub fn color(&mut self, spec: &str) -> Result<&mut Self, ParseColorError> {
+        let rgb: Rgb = spec.parse()?;
+        self.start_styles.push(Styles::StyleRgb(rgb));
+        Ok(self)
+    }
+
+    /// Sets the `hsl` color to the input text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("Well done is better than well said.").hsl(210.0, 1.0, 0.56).paint();
+    /// ```
+    ///
+    /// the hue is in degrees, the saturation and the lightness are between 0.0 and 1.0.
+    pub fn hsl(&mut self, hue: f64, saturation: f64, lightness: f64) -> &mut Self {
+        self.start_styles.push(Styles::StyleRgb(Rgb::from_hsl(hue, saturation, lightness)));
+        self
+    }
+
     /// Sets the `palette` color to the input text.
     ///
     /// # Example:
@@ -461,6 +918,48 @@ impl StyledText {
         self
     }
 
+    /// Sets the `curly_underline` format to the input text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").curly_underline().paint();
+    /// ```
+    ///
+    /// **on `ColorLevel::Ansi16` this is painted as a plain underline**
+    pub fn curly_underline(&mut self) -> &mut Self {
+        self.start_styles.push(underline_style::CURLY_UNDERLINE);
+        self
+    }
+
+    /// Sets the `dotted_underline` format to the input text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").dotted_underline().paint();
+    /// ```
+    ///
+    /// **on `ColorLevel::Ansi16` this is painted as a plain underline**
+    pub fn dotted_underline(&mut self) -> &mut Self {
+        self.start_styles.push(underline_style::DOTTED_UNDERLINE);
+        self
+    }
+
+    /// Sets the `dashed_underline` format to the input text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").dashed_underline().paint();
+    /// ```
+    ///
+    /// **on `ColorLevel::Ansi16` this is painted as a plain underline**
+    pub fn dashed_underline(&mut self) -> &mut Self {
+        self.start_styles.push(underline_style::DASHED_UNDERLINE);
+        self
+    }
+
     /// Sets the `overline` effect to the input text.
     ///
     /// # Example:
@@ -472,4 +971,586 @@ impl StyledText {
         self.start_styles.push(formatter::OVERLINE);
         self
     }
+
+    /// Sets the `inverse` effect to the input text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").inverse().paint();
+    /// ```
+    ///
+    /// **swaps the foreground and the background colors**
+    pub fn inverse(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::INVERSE);
+        self
+    }
+
+    /// Sets the `conceal` effect to the input text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").conceal().paint();
+    /// ```
+    ///
+    /// **hides the text**
+    pub fn conceal(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::CONCEAL);
+        self
+    }
+
+    /// Sets the `strikethrough` format to the input text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").strikethrough().paint();
+    /// ```
+    pub fn strikethrough(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::STRIKETHROUGH);
+        self
+    }
+
+    /// Sets the `double_underline` format to the input text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").double_underline().paint();
+    /// ```
+    ///
+    /// **some terminals show bold off instead**
+    pub fn double_underline(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::DOUBLE_UNDERLINE);
+        self
+    }
+
+    /// Sets the `proportional_spacing` effect to the input text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").proportional_spacing().paint();
+    /// ```
+    ///
+    /// **base on the terminal you are using this could not be applied**
+    pub fn proportional_spacing(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::PROPORTIONAL_SPACING);
+        self
+    }
+
+    /// Sets the `framed` effect to the input text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").framed().paint();
+    /// ```
+    ///
+    /// **base on the terminal you are using this could not be applied**
+    pub fn framed(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::FRAMED);
+        self
+    }
+
+    /// Sets the `encircled` effect to the input text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").encircled().paint();
+    /// ```
+    ///
+    /// **base on the terminal you are using this could not be applied**
+    pub fn encircled(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::ENCIRCLED);
+        self
+    }
+
+    /// Sets the `superscript` format to the input text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").superscript().paint();
+    /// ```
+    ///
+    /// **base on the terminal you are using this could not be applied**
+    pub fn superscript(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::SUPERSCRIPT);
+        self
+    }
+
+    /// Sets the `subscript` format to the input text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").subscript().paint();
+    /// ```
+    ///
+    /// **base on the terminal you are using this could not be applied**
+    pub fn subscript(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::SUBSCRIPT);
+        self
+    }
+
+    /// Sets the `normal_intensity` format to the input text, which turns `bold` and `faint` off.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").normal_intensity().paint();
+    /// ```
+    pub fn normal_intensity(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::NORMAL_INTENSITY);
+        self
+    }
+
+    /// Sets the `no_italic` format to the input text, which turns `italic` off.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").no_italic().paint();
+    /// ```
+    pub fn no_italic(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::NO_ITALIC);
+        self
+    }
+
+    /// Sets the `no_underline` format to the input text, which turns `underline` and `double_underline` off.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").no_underline().paint();
+    /// ```
+    pub fn no_underline(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::NO_UNDERLINE);
+        self
+    }
+
+    /// Sets the `no_blink` format to the input text, which turns `slow_blink` and `rapid_blink` off.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").no_blink().paint();
+    /// ```
+    pub fn no_blink(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::NO_BLINK);
+        self
+    }
+
+    /// Sets the `no_inverse` format to the input text, which turns `inverse` off.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").no_inverse().paint();
+    /// ```
+    pub fn no_inverse(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::NO_INVERSE);
+        self
+    }
+
+    /// Sets the `reveal` format to the input text, which turns `conceal` off.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").reveal().paint();
+    /// ```
+    pub fn reveal(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::REVEAL);
+        self
+    }
+
+    /// Sets the `no_strikethrough` format to the input text, which turns `strikethrough` off.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").no_strikethrough().paint();
+    /// ```
+    pub fn no_strikethrough(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::NO_STRIKETHROUGH);
+        self
+    }
+
+    /// Sets the `no_proportional_spacing` format to the input text, which turns `proportional_spacing` off.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").no_proportional_spacing().paint();
+    /// ```
+    pub fn no_proportional_spacing(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::NO_PROPORTIONAL_SPACING);
+        self
+    }
+
+    /// Sets the `no_framed` format to the input text, which turns `framed` and `encircled` off.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").no_framed().paint();
+    /// ```
+    pub fn no_framed(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::NO_FRAMED);
+        self
+    }
+
+    /// Sets the `no_overline` format to the input text, which turns `overline` off.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").no_overline().paint();
+    /// ```
+    pub fn no_overline(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::NO_OVERLINE);
+        self
+    }
+
+    /// Sets the `no_script` format to the input text, which turns `superscript` and `subscript` off.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// let styled_text = styled("The present is all we have to live in . . . or to lose.").no_script().paint();
+    /// ```
+    pub fn no_script(&mut self) -> &mut Self {
+        self.start_styles.push(formatter::NO_SCRIPT);
+        self
+    }
+}
+
+impl fmt::Display for StyledText {
+    /// Writes the escape codes and the text straight into the formatter.
+    ///
+    /// A link is written around the styled text, or its url after the text
+    /// if hyperlinks are not supported or the color level is `NoColor`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut backend = backend::AnsiBackend::with_writer(f);
+        self.render(&mut backend);
+        backend.result()
+    }
+}
+
+/// A struct for the escape codes `StyledText` writes before a piece of text or at the end.
+struct SgrCodes<'a> {
+    text: &'a StyledText,
+    /// Whether the start styles are written.
+    start: bool,
+    /// A color of the gradient.
+    color: Option<Color>,
+    /// The style to turn off at the end, following the reset mode of the text.
+    reset: Option<SpanStyle>,
+}
+
+impl SgrCodes<'_> {
+    /// Returns `true` if there are no codes to write.
+    fn is_empty(&self) -> bool {
+        let reset = self.reset.is_some_and(|style| {
+            self.text.reset_mode == ResetMode::Full ||
+                !style.off_formatters().is_empty() ||
+                style.fg.is_some() ||
+                style.bg.is_some() ||
+                style.ul.is_some()
+        });
+        !(self.start && self.text.start_params().next().is_some()) && self.color.is_none() && !reset
+    }
+
+    /// Passes the change of style with the codes to the backend, unless nothing changes.
+    fn set_style<B: backend::Backend + ?Sized>(&self, backend: &mut B, previous: &SpanStyle, style: &SpanStyle) {
+        if previous != style || !self.is_empty() {
+            backend.set_style_with_codes(previous, style, self);
+        }
+    }
+}
+
+impl fmt::Display for SgrCodes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut codes = SgrWriter::new(self.text.sgr_mode);
+        if self.start {
+            for (style, paint_type) in self.text.start_params() {
+                codes.param(f, |f| style.write_styles(f, Some(&paint_type)))?;
+            }
+        }
+        if let Some(color) = self.color {
+            codes.param(f, |f| color.write_styles(f, Some(&PaintType::FG)))?;
+        }
+        if let Some(style) = &self.reset {
+            match self.text.reset_mode {
+                ResetMode::Full => {
+                    codes.param(f, |f| f.write_str("0"))?;
+                }
+                ResetMode::Targeted => {
+                    for off in style.off_formatters().iter() {
+                        codes.param(f, |f| write!(f, "{}", off.code))?;
+                    }
+                    if style.fg.is_some() {
+                        codes.param(f, |f| f.write_str("39"))?;
+                    }
+                    if style.bg.is_some() {
+                        codes.param(f, |f| f.write_str("49"))?;
+                    }
+                    if style.ul.is_some() {
+                        codes.param(f, |f| f.write_str("59"))?;
+                    }
+                }
+            }
+        }
+        codes.finish(f)
+    }
+}
+
+/// A struct for writing SGR parameters as separate escape codes or combined into one.
+struct SgrWriter {
+    combined: bool,
+    count: usize,
+}
+
+impl SgrWriter {
+    /// Returns a writer for the given mode.
+    fn new(mode: SgrMode) -> Self {
+        SgrWriter { combined: mode == SgrMode::Combined, count: 0 }
+    }
+
+    /// Writes one parameter, `write_param` writes the parameter itself.
+    fn param<F>(&mut self, f: &mut fmt::Formatter<'_>, write_param: F) -> fmt::Result
+        where F: FnOnce(&mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        if !self.combined || self.count == 0 {
+            f.write_str("\x1b[")?;
+        } else {
+            f.write_str(";")?;
+        }
+        write_param(f)?;
+        if !self.combined {
+            f.write_str("m")?;
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Ends the combined escape code.
+    fn finish(&mut self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.combined && self.count > 0 {
+            f.write_str("m")?;
+        }
+        self.count = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paint_with_true_color() {
+        let txt = styled("abc").rgb(30, 144, 255).palette(208).bg().paint();
+        assert_eq!(txt, "\x1b[48;2;30;144;255m\x1b[48;5;208mabc\x1b[0m");
+    }
+
+    #[test]
+    fn paint_with_ansi_256() {
+        let txt = styled("abc").rgb(30, 144, 255).palette(208).bg().color_level(ColorLevel::Ansi256).paint();
+        assert_eq!(txt, "\x1b[48;5;33m\x1b[48;5;208mabc\x1b[0m");
+    }
+
+    #[test]
+    fn paint_with_ansi_16() {
+        let txt = styled("abc").rgb(250, 10, 10).palette(4).bold().color_level(ColorLevel::Ansi16).paint();
+        assert_eq!(txt, "\x1b[91m\x1b[34m\x1b[1mabc\x1b[0m");
+    }
+
+    #[test]
+    fn paint_with_targeted_reset() {
+        let txt = styled("abc").red().blue().bg().bold().faint().italic().reset_mode(ResetMode::Targeted).paint();
+        assert_eq!(txt, "\x1b[41m\x1b[44m\x1b[1m\x1b[2m\x1b[3mabc\x1b[22m\x1b[23m\x1b[49m");
+
+        let txt = styled("abc").underline().slow_blink().overline().green().reset_mode(ResetMode::Targeted).paint();
+        assert_eq!(txt, "\x1b[4m\x1b[5m\x1b[53m\x1b[32mabc\x1b[24m\x1b[25m\x1b[55m\x1b[39m");
+    }
+
+    #[test]
+    fn paint_nested_with_targeted_reset() {
+        let inner = styled("inner").red().reset_mode(ResetMode::Targeted).paint();
+        let outer = styled(&format!("a {} b", inner)).bold().paint();
+        assert_eq!(outer, "\x1b[1ma \x1b[31minner\x1b[39m b\x1b[0m");
+    }
+
+    #[test]
+    fn paint_targeted_reset_without_styles() {
+        let txt = styled("abc").reset_mode(ResetMode::Targeted).paint();
+        assert_eq!(txt, "abc");
+        let txt = styled("abc").red().reset().reset_mode(ResetMode::Targeted).paint();
+        assert_eq!(txt, "\x1b[31m\x1b[0mabc");
+    }
+
+    #[test]
+    fn display_matches_paint() {
+        let mut txt = styled("abc");
+        txt.rgb(1, 2, 3).bg().red().bold().underline().reset_mode(ResetMode::Targeted);
+        assert_eq!(format!("{}", txt), txt.paint());
+        assert_eq!(format!("{}", txt), "\x1b[48;2;1;2;3m\x1b[31m\x1b[1m\x1b[4mabc\x1b[22m\x1b[24m\x1b[39m\x1b[49m");
+    }
+
+    #[test]
+    fn write_to_writer() {
+        let mut out = Vec::new();
+        styled("abc").palette(9).bg().color_level(ColorLevel::Ansi16).write_to(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b[101mabc\x1b[0m");
+    }
+
+    #[test]
+    fn paint_combined_codes() {
+        let txt = styled("abc").red().bold().underline().sgr_mode(SgrMode::Combined).paint();
+        assert_eq!(txt, "\x1b[31;1;4mabc\x1b[0m");
+    }
+
+    #[test]
+    fn paint_combined_drops_overridden_colors() {
+        let txt = styled("abc").red().blue().bold().bold().sgr_mode(SgrMode::Combined).paint();
+        assert_eq!(txt, "\x1b[34;1mabc\x1b[0m");
+
+        let txt = styled("abc").red().bg().blue().green().sgr_mode(SgrMode::Combined).paint();
+        assert_eq!(txt, "\x1b[41;32mabc\x1b[0m");
+    }
+
+    #[test]
+    fn paint_combined_collapses_reset() {
+        let txt = styled("abc").red().bold().reset().sgr_mode(SgrMode::Combined).paint();
+        assert_eq!(txt, "abc");
+
+        let txt = styled("abc").red().reset().italic().sgr_mode(SgrMode::Combined).paint();
+        assert_eq!(txt, "\x1b[3mabc\x1b[0m");
+    }
+
+    #[test]
+    fn paint_combined_with_targeted_reset() {
+        let txt = styled("abc")
+            .bold()
+            .faint()
+            .rgb(1, 2, 3)
+            .sgr_mode(SgrMode::Combined)
+            .reset_mode(ResetMode::Targeted)
+            .paint();
+        assert_eq!(txt, "\x1b[1;2;38;2;1;2;3mabc\x1b[22;39m");
+    }
+
+    #[test]
+    fn paint_combined_with_color_level() {
+        let txt = styled("abc").rgb(255, 0, 0).bold().sgr_mode(SgrMode::Combined).color_level(ColorLevel::Ansi16).paint();
+        assert_eq!(txt, "\x1b[91;1mabc\x1b[0m");
+    }
+
+    #[test]
+    fn paint_with_new_formatters() {
+        let txt = styled("abc").inverse().strikethrough().double_underline().paint();
+        assert_eq!(txt, "\x1b[7m\x1b[9m\x1b[21mabc\x1b[0m");
+
+        let txt = styled("abc").framed().superscript().reset_mode(ResetMode::Targeted).paint();
+        assert_eq!(txt, "\x1b[51m\x1b[73mabc\x1b[54m\x1b[75m");
+    }
+
+    #[test]
+    fn paint_with_off_codes() {
+        let txt = styled("abc").conceal().reveal().no_strikethrough().sgr_mode(SgrMode::Combined).paint();
+        assert_eq!(txt, "\x1b[28;29mabc\x1b[0m");
+    }
+
+    #[test]
+    fn paint_underline_color_and_style() {
+        let txt = styled("abc").dotted_underline().red().ul().blue().paint();
+        assert_eq!(txt, "\x1b[4:4m\x1b[58;5;1m\x1b[34mabc\x1b[0m");
+
+        let txt = styled("abc").curly_underline().palette(9).ul().reset_mode(ResetMode::Targeted).paint();
+        assert_eq!(txt, "\x1b[4:3m\x1b[58;5;9mabc\x1b[24m\x1b[59m");
+    }
+
+    #[test]
+    fn paint_underline_with_color_level() {
+        let txt = styled("abc").curly_underline().rgb(255, 0, 0).ul().color_level(ColorLevel::Ansi256).paint();
+        assert_eq!(txt, "\x1b[4:3m\x1b[58;5;196mabc\x1b[0m");
+
+        let txt = styled("abc").dashed_underline().rgb(255, 0, 0).ul().color_level(ColorLevel::Ansi16).paint();
+        assert_eq!(txt, "\x1b[4mabc\x1b[0m");
+    }
+
+    #[test]
+    fn paint_combined_underline() {
+        let txt = styled("abc")
+            .underline()
+            .curly_underline()
+            .red()
+            .ul()
+            .green()
+            .ul()
+            .sgr_mode(SgrMode::Combined)
+            .paint();
+        assert_eq!(txt, "\x1b[4:3;58;5;2mabc\x1b[0m");
+    }
+
+    #[test]
+    fn paint_with_color_specs() {
+        let txt = styled("abc").hex("fff").unwrap().color("rgb(1, 2, 3)").unwrap().bg().paint();
+        assert_eq!(txt, "\x1b[48;2;255;255;255m\x1b[48;2;1;2;3mabc\x1b[0m");
+
+        let txt = styled("abc").hsl(0.0, 1.0, 0.5).paint();
+        assert_eq!(txt, "\x1b[38;2;255;0;0mabc\x1b[0m");
+    }
+
+    #[test]
+    fn invalid_color_specs_add_no_color() {
+        let mut txt = styled("abc");
+        assert_eq!(txt.hex("#12345").err(), Some(ParseColorError::InvalidHexLength(5)));
+        assert_eq!(txt.color("dodgerblu").err(), Some(ParseColorError::UnknownName("dodgerblu".to_string())));
+        assert_eq!(txt.paint(), "abc\x1b[0m");
+    }
+
+    #[test]
+    fn paint_with_gradient() {
+        let fade = Gradient::new(&[Rgb { r: 255, g: 0, b: 0 }, Rgb { r: 0, g: 0, b: 255 }]);
+        let txt = styled("abc").red().gradient(&fade).sgr_mode(SgrMode::Combined).paint();
+        assert_eq!(txt, "\x1b[38;2;255;0;0ma\x1b[38;2;128;0;128mb\x1b[38;2;0;0;255mc\x1b[0m");
+
+        let txt = styled("ab").gradient(&fade).color_level(ColorLevel::Ansi16).reset_mode(ResetMode::Targeted).paint();
+        assert_eq!(txt, "\x1b[91ma\x1b[34mb\x1b[39m");
+
+        let txt = styled("ab").gradient(&fade).color_level(ColorLevel::NoColor).paint();
+        assert_eq!(txt, "ab");
+    }
+
+    #[test]
+    fn paint_with_no_color() {
+        let txt = styled("abc").rgb(250, 10, 10).bold().color_level(ColorLevel::NoColor).paint();
+        assert_eq!(txt, "abc");
+    }
+
+    #[test]
+    fn paint_link_with_styles() {
+        let txt = styled("a").red().bold().link("https://x.y/ä").sgr_mode(SgrMode::Combined).paint();
+        assert_eq!(txt, "\x1b]8;;https://x.y/%C3%A4\x1b\\\x1b[31;1ma\x1b[0m\x1b]8;;\x1b\\");
+        let txt = styled("a").link_with_id("http://x", "7").paint();
+        assert_eq!(txt, "\x1b]8;id=7;http://x\x1b\\a\x1b[0m\x1b]8;;\x1b\\");
+    }
+
+    #[test]
+    fn paint_link_fallback() {
+        let txt = styled("a").italic().link("http://x").hyperlinks(false).paint();
+        assert_eq!(txt, "\x1b[3ma\x1b[0m (http://x)");
+        let txt = styled("a").italic().link("http://x").color_level(ColorLevel::NoColor).paint();
+        assert_eq!(txt, "a (http://x)");
+        let txt = styled("a").link("http://x").detect_hyperlinks().paint();
+        assert_eq!(txt.contains("\x1b]8;;"), detect::stdout_hyperlinks());
+        let txt = styled("a").link("http://x/\x1b[2J\x07").hyperlinks(false).paint();
+        assert_eq!(txt, "a\x1b[0m (http://x/%1B[2J%07)");
+    }
 }
@@ -0,0 +1,315 @@
+//! A module for writing styled text with inline markup.
+//!
+//! This module provides `parse` and `parse_lenient`, which read a markup string like
+//! `"[bold red]error[/] at [u]src/main.rs[/u]"` into a `StyledDocument`.
+//!
+//! A tag holds styles separated by spaces and is closed by `[/]` or by a closing tag with
+//! the same styles, like `[/bold red]`. Tags can be nested, the inner tags inherit the
+//! styles of the outer ones. The styles are:
+//!
+//! - the formatters: `bold` (`b`), `faint` (`dim`), `italic` (`i`), `underline` (`u`),
+//!   `double_underline` (`uu`), `curly_underline`, `dotted_underline`, `dashed_underline`,
+//!   `slow_blink` (`blink`), `rapid_blink`, `inverse` (`reverse`), `conceal` (`hidden`),
+//!   `strikethrough` (`strike`, `s`), `overline`, `framed`, `encircled`, `superscript`
+//!   and `subscript`
+//! - the 16 basic colors like `red` or `bright_blue`
+//! - palette colors like `color(208)`
+//! - rgb colors like `#ff8800` or CSS names like `dodgerblue`
+//! - any color after `on`, which paints it as background, like `on blue`
+//!
+//! `\[` writes a literal `[` and `\\` a literal `\`.
+
+// =======================================================================
+
+use std::{ error::Error, fmt };
+
+use crate::{ style::{ ParseStyleError, Style }, StyledDocument, StyledText };
+
+// =======================================================================
+
+/// An enum representing why a markup string could not be parsed.
+///
+/// Every error holds the byte position of the tag in the markup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MarkupError {
+    /// A tag with a style that is not known.
+    UnknownStyle { style: String, position: usize },
+    /// A `[` without its closing `]`.
+    UnterminatedTag { position: usize },
+    /// A closing tag without an open tag.
+    UnexpectedClose { tag: String, position: usize },
+    /// A closing tag that does not match the last open tag.
+    MismatchedClose { open: String, close: String, position: usize },
+    /// A tag that is still open at the end of the markup, with the position of the open tag.
+    UnclosedTag { tag: String, position: usize },
+}
+
+impl fmt::Display for MarkupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarkupError::UnknownStyle { style, position } => {
+                write!(f, "unknown style `{}` in the tag at byte {}", style, position)
+            }
+            MarkupError::UnterminatedTag { position } => {
+                write!(f, "the tag at byte {} is missing its closing `]`", position)
+            }
+            MarkupError::UnexpectedClose { tag, position } => {
+                write!(f, "`[/{}]` at byte {} closes no tag", tag, position)
+            }
+            MarkupError::MismatchedClose { open, close, position } => {
+                write!(f, "`[/{}]` at byte {} does not close the open tag `[{}]`", close, position, open)
+            }
+            MarkupError::UnclosedTag { tag, position } => {
+                write!(f, "the tag `[{}]` at byte {} is never closed", tag, position)
+            }
+        }
+    }
+}
+
+impl Error for MarkupError {}
+
+/// Parses a markup string into a document.
+///
+/// # Example:
+/// ```
+/// use term_tools::markup;
+/// let doc = markup::parse("[bold red]error[/] at [u]src/main.rs[/u]")?;
+/// assert_eq!(doc.paint(), "\x1b[1;31merror\x1b[0m at \x1b[4msrc/main.rs\x1b[0m");
+/// # Ok::<(), term_tools::markup::MarkupError>(())
+/// ```
+///
+/// Unknown styles, unbalanced tags and a `[` without `]` are errors,
+/// use `parse_lenient` to keep them as text instead.
+pub fn parse(markup: &str) -> Result<StyledDocument, MarkupError> {
+    MarkupParser { lenient: false }.parse(markup)
+}
+
+/// Parses a markup string into a document and never fails.
+///
+/// Tags with unknown styles, closing tags that close nothing or the wrong tag and a `[`
+/// without `]` are kept as text, and tags that are still open at the end are closed.
+///
+/// # Example:
+/// ```
+/// use term_tools::markup;
+/// let doc = markup::parse_lenient("[b]array[0][/b]");
+/// assert_eq!(doc.paint(), "\x1b[1marray[0]\x1b[0m");
+/// ```
+pub fn parse_lenient(markup: &str) -> StyledDocument {
+    (MarkupParser { lenient: true }).parse(markup).unwrap_or_default()
+}
+
+/// A struct representing an open tag with the fragments inside it.
+struct OpenTag {
+    tag: String,
+    position: usize,
+    styled: StyledText,
+    inner: StyledDocument,
+}
+
+/// A struct for parsing markup, strictly or leniently.
+struct MarkupParser {
+    lenient: bool,
+}
+
+impl MarkupParser {
+    /// Parses the markup, the open tags are kept on a stack until they are closed.
+    fn parse(&self, markup: &str) -> Result<StyledDocument, MarkupError> {
+        let mut root = StyledDocument::new();
+        let mut open: Vec<OpenTag> = vec![];
+        let mut text = String::new();
+        let mut rest = markup;
+        while let Some(i) = rest.find(['[', '\\']) {
+            text.push_str(&rest[..i]);
+            let position = markup.len() - rest.len() + i;
+            rest = &rest[i..];
+            if let Some(escaped) = rest.strip_prefix("\\[").or_else(|| rest.strip_prefix("\\\\")) {
+                text.push_str(&rest[1..2]);
+                rest = escaped;
+                continue;
+            }
+            if rest.starts_with('\\') {
+                text.push('\\');
+                rest = &rest[1..];
+                continue;
+            }
+            let Some(end) = rest.find(']') else {
+                if self.lenient {
+                    break;
+                }
+                return Err(MarkupError::UnterminatedTag { position });
+            };
+            let tag = &rest[1..end];
+            let literal = &rest[..end + 1];
+            rest = &rest[end + 1..];
+
+            match self.tag(tag, position, &open) {
+                Ok(Tag::Open(styled)) => {
+                    flush(&mut text, &mut root, &mut open);
+                    open.push(OpenTag {
+                        tag: tag.trim().to_string(),
+                        position,
+                        styled,
+                        inner: StyledDocument::new(),
+                    });
+                }
+                Ok(Tag::Close) => {
+                    flush(&mut text, &mut root, &mut open);
+                    close_last(&mut root, &mut open);
+                }
+                Err(_) if self.lenient => text.push_str(literal),
+                Err(err) => {
+                    return Err(err);
+                }
+            }
+        }
+        text.push_str(rest);
+        flush(&mut text, &mut root, &mut open);
+
+        if let Some(last) = open.last() {
+            if !self.lenient {
+                return Err(MarkupError::UnclosedTag { tag: last.tag.clone(), position: last.position });
+            }
+        }
+        while !open.is_empty() {
+            close_last(&mut root, &mut open);
+        }
+        Ok(root)
+    }
+
+    /// Reads the content of a tag, a closing tag must close the last open tag.
+    fn tag(&self, tag: &str, position: usize, open: &[OpenTag]) -> Result<Tag, MarkupError> {
+        let Some(close) = tag.strip_prefix('/') else {
+            return styled_tag(tag, position).map(Tag::Open);
+        };
+        let close = close.trim();
+        match open.last() {
+            None => Err(MarkupError::UnexpectedClose { tag: close.to_string(), position }),
+            Some(last) if !close.is_empty() && close != last.tag => {
+                Err(MarkupError::MismatchedClose {
+                    open: last.tag.clone(),
+                    close: close.to_string(),
+                    position,
+                })
+            }
+            Some(_) => Ok(Tag::Close),
+        }
+    }
+}
+
+/// An enum representing a tag that was read.
+enum Tag {
+    /// An open tag with its styles.
+    Open(StyledText),
+    /// A closing tag.
+    Close,
+}
+
+/// Adds the collected text to the innermost open tag, or to the document if no tag is open.
+fn flush(text: &mut String, root: &mut StyledDocument, open: &mut [OpenTag]) {
+    if text.is_empty() {
+        return;
+    }
+    match open.last_mut() {
+        Some(last) => last.inner.text(text),
+        None => root.text(text),
+    };
+    text.clear();
+}
+
+/// Closes the innermost open tag and nests it into its parent.
+fn close_last(root: &mut StyledDocument, open: &mut Vec<OpenTag>) {
+    if let Some(last) = open.pop() {
+        match open.last_mut() {
+            Some(parent) => parent.inner.nest(&last.styled, &last.inner),
+            None => root.nest(&last.styled, &last.inner),
+        };
+    }
+}
+
+/// Returns a `StyledText` without text that has the styles of the tag.
+fn styled_tag(tag: &str, position: usize) -> Result<StyledText, MarkupError> {
+    let style: Style = tag
+        .parse()
+        .map_err(|err: ParseStyleError| MarkupError::UnknownStyle { style: err.style, position })?;
+    Ok(style.apply(""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paint(markup: &str) -> String {
+        parse(markup).unwrap().paint()
+    }
+
+    #[test]
+    fn parse_plain_text() {
+        assert_eq!(paint("no tags here"), "no tags here");
+        assert_eq!(paint(""), "");
+    }
+
+    #[test]
+    fn parse_tags() {
+        assert_eq!(paint("[bold red]error[/] at [u]path[/u]"), "\x1b[1;31merror\x1b[0m at \x1b[4mpath\x1b[0m");
+        assert_eq!(paint("[on blue]a[/on blue]"), "\x1b[44ma\x1b[0m");
+        assert_eq!(paint("[red on white]a[/]"), "\x1b[31;47ma\x1b[0m");
+        assert_eq!(paint("[color(208)]a[/]"), "\x1b[38;5;208ma\x1b[0m");
+        assert_eq!(paint("[#ff8800 on dodgerblue]a[/]"), "\x1b[38;2;255;136;0;48;2;30;144;255ma\x1b[0m");
+    }
+
+    #[test]
+    fn parse_nested_tags() {
+        assert_eq!(paint("[red]a[b]b[/b]c[/red]d"), "\x1b[31ma\x1b[1mb\x1b[22mc\x1b[0md");
+        assert_eq!(paint("[red][blue]a[/][/]"), "\x1b[34ma\x1b[0m");
+    }
+
+    #[test]
+    fn parse_escapes() {
+        assert_eq!(paint("\\[b]not bold\\[/b]"), "[b]not bold[/b]");
+        assert_eq!(paint("a\\\\[b]b[/b]"), "a\\\x1b[1mb\x1b[0m");
+        assert_eq!(paint("a\\nb ]"), "a\\nb ]");
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            parse("[bold foo]a[/]").err(),
+            Some(MarkupError::UnknownStyle { style: "foo".to_string(), position: 0 })
+        );
+        assert_eq!(parse("ab[bold").err(), Some(MarkupError::UnterminatedTag { position: 2 }));
+        assert_eq!(
+            parse("a[/b]").err(),
+            Some(MarkupError::UnexpectedClose { tag: "b".to_string(), position: 1 })
+        );
+        assert_eq!(
+            parse("[b]a[/i]").err(),
+            Some(MarkupError::MismatchedClose { open: "b".to_string(), close: "i".to_string(), position: 4 })
+        );
+        assert_eq!(
+            parse("[b]a[i]b[/i]").err(),
+            Some(MarkupError::UnclosedTag { tag: "b".to_string(), position: 0 })
+        );
+        assert_eq!(
+            parse("[]").err(),
+            Some(MarkupError::UnknownStyle { style: "".to_string(), position: 0 })
+        );
+        assert_eq!(
+            parse("[on]").err(),
+            Some(MarkupError::UnknownStyle { style: "on".to_string(), position: 0 })
+        );
+    }
+
+    #[test]
+    fn error_messages() {
+        let err = parse("[b]a[/i]").unwrap_err();
+        assert_eq!(err.to_string(), "`[/i]` at byte 4 does not close the open tag `[b]`");
+    }
+
+    #[test]
+    fn parse_leniently() {
+        assert_eq!(parse_lenient("[b]list[0][/b]").paint(), "\x1b[1mlist[0]\x1b[0m");
+        assert_eq!(parse_lenient("a[/b] [b]c").paint(), "a[/b] \x1b[1mc\x1b[0m");
+        assert_eq!(parse_lenient("[b]a[/i][/b] [x").paint(), "\x1b[1ma[/i]\x1b[0m [x");
+    }
+}
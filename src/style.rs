@@ -0,0 +1,593 @@
+//! A module for styles that are not bound to a text.
+//!
+//! This module provides a struct `Style` with the same builder methods as `StyledText`.
+//! A style can be defined once, even as a `const`, and applied to any number of texts.
+//! Styles can also be parsed from a string of style names like `"bold red on white"`.
+
+// =======================================================================
+
+use std::{ error::Error, fmt, str::FromStr };
+
+use crate::{
+    styles::{
+        basic_color,
+        color::Color,
+        formatter::{ self, Formatter, FormatterSet },
+        paint_type::PaintType,
+        palette::PaletteColor,
+        rgb::Rgb,
+        underline_style::{ self, UnderlineStyle },
+        find_name,
+        Styles,
+    },
+    StyledText,
+};
+
+// =======================================================================
+
+/// A struct representing a reusable style.
+///
+/// # Example:
+/// ```
+/// use term_tools::Style;
+/// const WARNING: Style = Style::new().yellow().bold();
+/// let first = WARNING.paint("disk almost full");
+/// let second = WARNING.apply("memory almost full").underline().paint();
+/// assert_eq!(first, "\x1b[1m\x1b[33mdisk almost full\x1b[0m");
+/// ```
+///
+/// The `fg`, `bg` and `ul` methods work like the ones of `StyledText`:
+/// the colors before them are painted as foreground, background or underline color,
+/// and the colors after the last of them are painted as foreground.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Style {
+    reset: bool,
+    fg: Option<Color>,
+    bg: Option<Color>,
+    ul: Option<Color>,
+    pending: Option<Color>,
+    underline_style: Option<UnderlineStyle>,
+    formatters: FormatterSet,
+}
+
+impl Style {
+    /// Returns a style without any color or formatter.
+    pub const fn new() -> Self {
+        Style {
+            reset: false,
+            fg: None,
+            bg: None,
+            ul: None,
+            pending: None,
+            underline_style: None,
+            formatters: FormatterSet::new(),
+        }
+    }
+
+    /// Returns a `StyledText` of the text with this style.
+    ///
+    /// The returned `StyledText` can be styled further before it is painted.
+    pub fn apply(&self, text: &str) -> StyledText {
+        let mut styled = StyledText::new(text.to_string());
+        styled.style(self);
+        styled
+    }
+
+    /// Paints the text with this style.
+    pub fn paint(&self, text: &str) -> String {
+        self.apply(text).paint()
+    }
+
+    /// Returns the list of styles that a `StyledText` paints for this style.
+    pub(crate) fn to_styles(self) -> Vec<Styles> {
+        let mut styles: Vec<Styles> = vec![];
+        if self.reset {
+            styles.push(formatter::RESET);
+        }
+        styles.extend(self.formatters.iter().map(Styles::StyleFormatter));
+        if let Some(u) = self.underline_style {
+            styles.push(Styles::StyleUnderline(u));
+        }
+        if let Some(c) = self.fg {
+            styles.push(c.into());
+            styles.push(Styles::StylePaintType(PaintType::FG));
+        }
+        if let Some(c) = self.bg {
+            styles.push(c.into());
+            styles.push(Styles::StylePaintType(PaintType::BG));
+        }
+        if let Some(c) = self.ul {
+            styles.push(c.into());
+            styles.push(Styles::StylePaintType(PaintType::UL));
+        }
+        if let Some(c) = self.pending {
+            styles.push(c.into());
+        }
+        styles
+    }
+
+    /// Adds a style like `StyledText` would.
+    const fn push(mut self, style: Styles) -> Self {
+        match style {
+            Styles::StyleBasicColor(c) => {
+                self.pending = Some(Color::Basic(c));
+            }
+            Styles::StylePaletteColor(c) => {
+                self.pending = Some(Color::Palette(c));
+            }
+            Styles::StyleRgb(c) => {
+                self.pending = Some(Color::Rgb(c));
+            }
+            Styles::StylePaintType(p) => {
+                if self.pending.is_some() {
+                    match p {
+                        PaintType::FG => {
+                            self.fg = self.pending;
+                        }
+                        PaintType::BG => {
+                            self.bg = self.pending;
+                        }
+                        PaintType::UL => {
+                            self.ul = self.pending;
+                        }
+                    }
+                }
+                self.pending = None;
+            }
+            Styles::StyleFormatter(f) if f.code == 0 => {
+                self = Style::new();
+                self.reset = true;
+            }
+            Styles::StyleFormatter(f) => {
+                self.formatters.apply(&f);
+                // off codes are kept, so the style can turn off what the text around it set
+                if f.is_off_code() {
+                    self.formatters.insert(&f);
+                } else if let Some(code) = f.off_code() {
+                    self.formatters.remove(&Formatter { code });
+                }
+                if underline_style::is_underline_formatter(&f) {
+                    self.underline_style = None;
+                }
+            }
+            Styles::StyleUnderline(u) => {
+                self.formatters.remove(&Formatter { code: 4 });
+                self.formatters.remove(&Formatter { code: 21 });
+                self.formatters.remove(&Formatter { code: 24 });
+                self.underline_style = Some(u);
+            }
+        }
+        self
+    }
+
+    /// Paints the colors before this method as foreground, see [`StyledText::fg`].
+    pub const fn fg(self) -> Self {
+        self.push(Styles::StylePaintType(PaintType::FG))
+    }
+
+    /// Paints the colors before this method as background, see [`StyledText::bg`].
+    pub const fn bg(self) -> Self {
+        self.push(Styles::StylePaintType(PaintType::BG))
+    }
+
+    /// Paints the colors before this method as underline color, see [`StyledText::ul`].
+    pub const fn ul(self) -> Self {
+        self.push(Styles::StylePaintType(PaintType::UL))
+    }
+
+    // Colors
+
+    /// Sets the `rgb` color.
+    pub const fn rgb(self, r: u8, g: u8, b: u8) -> Self {
+        self.push(Styles::StyleRgb(Rgb { r, g, b }))
+    }
+
+    /// Sets the `palette` color.
+    ///
+    /// the index should be 8 bit color between 0 to 255.
+    pub const fn palette(self, index: u8) -> Self {
+        self.push(Styles::StylePaletteColor(PaletteColor { index }))
+    }
+
+    /// Sets the `black` color.
+    pub const fn black(self) -> Self {
+        self.push(basic_color::BLACK)
+    }
+
+    /// Sets the `red` color.
+    pub const fn red(self) -> Self {
+        self.push(basic_color::RED)
+    }
+
+    /// Sets the `green` color.
+    pub const fn green(self) -> Self {
+        self.push(basic_color::GREEN)
+    }
+
+    /// Sets the `yellow` color.
+    pub const fn yellow(self) -> Self {
+        self.push(basic_color::YELLOW)
+    }
+
+    /// Sets the `blue` color.
+    pub const fn blue(self) -> Self {
+        self.push(basic_color::BLUE)
+    }
+
+    /// Sets the `magenta` color.
+    pub const fn magenta(self) -> Self {
+        self.push(basic_color::MAGENTA)
+    }
+
+    /// Sets the `cyan` color.
+    pub const fn cyan(self) -> Self {
+        self.push(basic_color::CYAN)
+    }
+
+    /// Sets the `white` color.
+    pub const fn white(self) -> Self {
+        self.push(basic_color::WHITE)
+    }
+
+    /// Sets the `gray` color.
+    pub const fn gray(self) -> Self {
+        self.push(basic_color::GRAY)
+    }
+
+    /// Sets the `bright_red` color.
+    pub const fn bright_red(self) -> Self {
+        self.push(basic_color::BRIGHT_RED)
+    }
+
+    /// Sets the `bright_green` color.
+    pub const fn bright_green(self) -> Self {
+        self.push(basic_color::BRIGHT_GREEN)
+    }
+
+    /// Sets the `bright_yellow` color.
+    pub const fn bright_yellow(self) -> Self {
+        self.push(basic_color::BRIGHT_YELLOW)
+    }
+
+    /// Sets the `bright_blue` color.
+    pub const fn bright_blue(self) -> Self {
+        self.push(basic_color::BRIGHT_BLUE)
+    }
+
+    /// Sets the `bright_magenta` color.
+    pub const fn bright_magenta(self) -> Self {
+        self.push(basic_color::BRIGHT_MAGENTA)
+    }
+
+    /// Sets the `bright_cyan` color.
+    pub const fn bright_cyan(self) -> Self {
+        self.push(basic_color::BRIGHT_CYAN)
+    }
+
+    /// Sets the `bright_white` color.
+    pub const fn bright_white(self) -> Self {
+        self.push(basic_color::BRIGHT_WHITE)
+    }
+
+    // Formatters
+
+    /// Sets the `reset` effect.
+    ///
+    /// ** this will reset all the effects, colors and formatters that are called before this**
+    pub const fn reset(self) -> Self {
+        self.push(formatter::RESET)
+    }
+
+    /// Sets the `bold` format.
+    pub const fn bold(self) -> Self {
+        self.push(formatter::BOLD)
+    }
+
+    /// Sets the `faint` format.
+    pub const fn faint(self) -> Self {
+        self.push(formatter::FAINT)
+    }
+
+    /// Sets the `italic` format.
+    pub const fn italic(self) -> Self {
+        self.push(formatter::ITALIC)
+    }
+
+    /// Sets the `underline` format.
+    pub const fn underline(self) -> Self {
+        self.push(formatter::UNDERLINE)
+    }
+
+    /// Sets the `slow_blink` effect.
+    pub const fn slow_blink(self) -> Self {
+        self.push(formatter::SLOW_BLINK)
+    }
+
+    /// Sets the `rapid_blink` effect.
+    pub const fn rapid_blink(self) -> Self {
+        self.push(formatter::RAPID_BLINK)
+    }
+
+    /// Sets the `curly_underline` format.
+    pub const fn curly_underline(self) -> Self {
+        self.push(underline_style::CURLY_UNDERLINE)
+    }
+
+    /// Sets the `dotted_underline` format.
+    pub const fn dotted_underline(self) -> Self {
+        self.push(underline_style::DOTTED_UNDERLINE)
+    }
+
+    /// Sets the `dashed_underline` format.
+    pub const fn dashed_underline(self) -> Self {
+        self.push(underline_style::DASHED_UNDERLINE)
+    }
+
+    /// Sets the `overline` effect.
+    pub const fn overline(self) -> Self {
+        self.push(formatter::OVERLINE)
+    }
+
+    /// Sets the `inverse` effect.
+    pub const fn inverse(self) -> Self {
+        self.push(formatter::INVERSE)
+    }
+
+    /// Sets the `conceal` effect.
+    pub const fn conceal(self) -> Self {
+        self.push(formatter::CONCEAL)
+    }
+
+    /// Sets the `strikethrough` format.
+    pub const fn strikethrough(self) -> Self {
+        self.push(formatter::STRIKETHROUGH)
+    }
+
+    /// Sets the `double_underline` format.
+    pub const fn double_underline(self) -> Self {
+        self.push(formatter::DOUBLE_UNDERLINE)
+    }
+
+    /// Sets the `proportional_spacing` effect.
+    pub const fn proportional_spacing(self) -> Self {
+        self.push(formatter::PROPORTIONAL_SPACING)
+    }
+
+    /// Sets the `framed` effect.
+    pub const fn framed(self) -> Self {
+        self.push(formatter::FRAMED)
+    }
+
+    /// Sets the `encircled` effect.
+    pub const fn encircled(self) -> Self {
+        self.push(formatter::ENCIRCLED)
+    }
+
+    /// Sets the `superscript` format.
+    pub const fn superscript(self) -> Self {
+        self.push(formatter::SUPERSCRIPT)
+    }
+
+    /// Sets the `subscript` format.
+    pub const fn subscript(self) -> Self {
+        self.push(formatter::SUBSCRIPT)
+    }
+
+    /// Sets the `normal_intensity` format, which turns `bold` and `faint` off.
+    pub const fn normal_intensity(self) -> Self {
+        self.push(formatter::NORMAL_INTENSITY)
+    }
+
+    /// Sets the `no_italic` format, which turns `italic` off.
+    pub const fn no_italic(self) -> Self {
+        self.push(formatter::NO_ITALIC)
+    }
+
+    /// Sets the `no_underline` format, which turns `underline` and `double_underline` off.
+    pub const fn no_underline(self) -> Self {
+        self.push(formatter::NO_UNDERLINE)
+    }
+
+    /// Sets the `no_blink` format, which turns `slow_blink` and `rapid_blink` off.
+    pub const fn no_blink(self) -> Self {
+        self.push(formatter::NO_BLINK)
+    }
+
+    /// Sets the `no_inverse` format, which turns `inverse` off.
+    pub const fn no_inverse(self) -> Self {
+        self.push(formatter::NO_INVERSE)
+    }
+
+    /// Sets the `reveal` format, which turns `conceal` off.
+    pub const fn reveal(self) -> Self {
+        self.push(formatter::REVEAL)
+    }
+
+    /// Sets the `no_strikethrough` format, which turns `strikethrough` off.
+    pub const fn no_strikethrough(self) -> Self {
+        self.push(formatter::NO_STRIKETHROUGH)
+    }
+
+    /// Sets the `no_proportional_spacing` format, which turns `proportional_spacing` off.
+    pub const fn no_proportional_spacing(self) -> Self {
+        self.push(formatter::NO_PROPORTIONAL_SPACING)
+    }
+
+    /// Sets the `no_framed` format, which turns `framed` and `encircled` off.
+    pub const fn no_framed(self) -> Self {
+        self.push(formatter::NO_FRAMED)
+    }
+
+    /// Sets the `no_overline` format, which turns `overline` off.
+    pub const fn no_overline(self) -> Self {
+        self.push(formatter::NO_OVERLINE)
+    }
+
+    /// Sets the `no_script` format, which turns `superscript` and `subscript` off.
+    pub const fn no_script(self) -> Self {
+        self.push(formatter::NO_SCRIPT)
+    }
+}
+
+/// An error for a style string with a word that is not a known style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseStyleError {
+    /// The word that is not a known style, or the whole string if it holds no style.
+    pub style: String,
+}
+
+impl fmt::Display for ParseStyleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown style `{}`", self.style)
+    }
+}
+
+impl Error for ParseStyleError {}
+
+impl FromStr for Style {
+    type Err = ParseStyleError;
+
+    /// Parses styles separated by spaces, like `"bold red on white"`.
+    ///
+    /// The styles are the names that `styled!` tags use: formatters like `bold` (`b`),
+    /// `italic` (`i`), `no_italic` or `curly_underline`,
+    /// the 16 basic colors like `red` or `bright_blue`, palette colors like `color(208)`
+    /// and rgb colors like `#ff8800` or `dodgerblue`. A color after `on` is painted as background.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::Style;
+    /// let style: Style = "bold #ff0000 on color(236)".parse()?;
+    /// assert_eq!(style, Style::new().bold().rgb(255, 0, 0).fg().palette(236).bg());
+    /// # Ok::<(), term_tools::style::ParseStyleError>(())
+    /// ```
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let unknown = |style: &str| ParseStyleError { style: style.to_string() };
+        let mut style = Style::new();
+        let mut words = spec.split_whitespace();
+        let mut any = false;
+        while let Some(word) = words.next() {
+            any = true;
+            style = if word == "on" {
+                let color = words.next().ok_or_else(|| unknown(word))?;
+                with_color(style, color).ok_or_else(|| unknown(color))?.bg()
+            } else if let Some(colored) = with_color(style, word) {
+                colored.fg()
+            } else {
+                with_formatter(style, word).ok_or_else(|| unknown(word))?
+            };
+        }
+        if !any {
+            return Err(unknown(spec));
+        }
+        Ok(style)
+    }
+}
+
+/// Returns the style with the formatter of the given name, or `None` if there is none.
+fn with_formatter(style: Style, name: &str) -> Option<Style> {
+    match find_name(name.as_bytes(), 0, name.len()) {
+        Some(s @ (Styles::StyleFormatter(_) | Styles::StyleUnderline(_))) => Some(style.push(s)),
+        _ => None,
+    }
+}
+
+/// Returns the style with the color of the given name, or `None` if there is none.
+///
+/// The 16 basic colors come first, then `color(n)` for palette colors
+/// and then everything `Rgb` can be parsed from.
+fn with_color(style: Style, name: &str) -> Option<Style> {
+    if let Some(s @ Styles::StyleBasicColor(_)) = find_name(name.as_bytes(), 0, name.len()) {
+        return Some(style.push(s));
+    }
+    if let Some(index) = name.strip_prefix("color(").and_then(|n| n.strip_suffix(')')) {
+        return index.trim().parse().ok().map(|index| style.palette(index));
+    }
+    let rgb = name.parse::<Rgb>().ok()?;
+    Some(style.rgb(rgb.r, rgb.g, rgb.b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ styled, styles::basic_color::BasicColor };
+
+    const ERROR: Style = Style::new().red().bold();
+
+    #[test]
+    fn const_style() {
+        assert_eq!(ERROR.paint("a"), "\x1b[1m\x1b[31ma\x1b[0m");
+        assert_eq!(ERROR.paint("b"), "\x1b[1m\x1b[31mb\x1b[0m");
+    }
+
+    #[test]
+    fn style_is_copy() {
+        let style = ERROR;
+        let copy = style;
+        assert_eq!(style, copy);
+    }
+
+    #[test]
+    fn style_with_off_codes() {
+        assert_eq!(Style::new().no_italic().paint("a"), "\x1b[23ma\x1b[0m");
+        assert_eq!(Style::new().bold().normal_intensity().paint("a"), "\x1b[22ma\x1b[0m");
+        assert_eq!(Style::new().no_underline().double_underline().paint("a"), "\x1b[21ma\x1b[0m");
+    }
+
+    #[test]
+    fn style_with_underline_color() {
+        const SQUIGGLE: Style = Style::new().curly_underline().rgb(255, 0, 0).ul();
+        assert_eq!(SQUIGGLE.paint("a"), "\x1b[4:3m\x1b[58;2;255;0;0ma\x1b[0m");
+        assert_eq!(SQUIGGLE.underline().paint("a"), "\x1b[4m\x1b[58;2;255;0;0ma\x1b[0m");
+    }
+
+    #[test]
+    fn apply_and_style_further() {
+        let txt = ERROR.apply("a").underline().paint();
+        assert_eq!(txt, "\x1b[1m\x1b[31m\x1b[4ma\x1b[0m");
+    }
+
+    #[test]
+    fn fg_and_bg_like_styled_text() {
+        let style = Style::new().red().bg().blue().rgb(1, 2, 3).fg().palette(9);
+        assert_eq!(style.paint("a"), "\x1b[38;2;1;2;3m\x1b[41m\x1b[38;5;9ma\x1b[0m");
+
+        let style = Style::new().black().fg().red().bg();
+        assert_eq!(style.paint("a"), styled("a").black().fg().red().bg().paint());
+    }
+
+    #[test]
+    fn reset_clears_previous_styles() {
+        let style = Style::new().red().bold().reset().italic();
+        assert_eq!(style.paint("a"), "\x1b[0m\x1b[3ma\x1b[0m");
+    }
+
+    #[test]
+    fn formatters_are_applied_once() {
+        let style = Style::new().bold().bold().faint();
+        assert_eq!(style.paint("a"), "\x1b[1m\x1b[2ma\x1b[0m");
+    }
+
+    #[test]
+    fn default_style_is_empty() {
+        assert_eq!(Style::default(), Style::new());
+        assert_eq!(Style::new().paint("a"), "a\x1b[0m");
+    }
+
+    #[test]
+    fn basic_color_methods() {
+        let style = Style::new().bright_cyan();
+        assert_eq!(style.pending, Some(Color::Basic(BasicColor { fg: 96, bg: 106 })));
+    }
+
+    #[test]
+    fn parse_style() {
+        assert_eq!("bold red".parse(), Ok(Style::new().bold().red().fg()));
+        assert_eq!("u on blue".parse(), Ok(Style::new().underline().blue().bg()));
+        assert_eq!("no_italic on grey".parse(), Ok(Style::new().no_italic().gray().bg()));
+        assert_eq!("color(208) on #000000".parse(), Ok(Style::new().palette(208).fg().rgb(0, 0, 0).bg()));
+        assert_eq!("bold rde".parse::<Style>(), Err(ParseStyleError { style: "rde".to_string() }));
+        assert_eq!("on".parse::<Style>(), Err(ParseStyleError { style: "on".to_string() }));
+        assert_eq!(" ".parse::<Style>(), Err(ParseStyleError { style: " ".to_string() }));
+    }
+}
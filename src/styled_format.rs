@@ -0,0 +1,430 @@
+//! A module for the `styled!` macro.
+//!
+//! This module provides the struct `StyledFormat` that the `styled!` macro expands into,
+//! and the const function that checks the format string of the macro while compiling.
+//! The tags of the format string are the lowercase names of the constants in
+//! `styles::basic_color`, `styles::formatter` and `styles::underline_style`,
+//! and the same short aliases like `b` or `grey` that `Style::from_str` accepts.
+
+// =======================================================================
+
+use std::fmt;
+
+use crate::{
+    ansi_code::ANSIEscapeCode,
+    styles::{ color_level::ColorLevel, find_name, paint_type::PaintType, span_style::SpanStyle, Styles },
+};
+
+// =======================================================================
+
+/// Paints a format string with styles in it, checked while compiling.
+///
+/// Tags like `{bold red}` set styles until the next `{/}`, tags can be nested.
+/// A color after `on` is painted as background, like `{on blue}`.
+/// `{}` placeholders are filled with the arguments, which only have to implement `Display`,
+/// and `{{` and `}}` write literal braces.
+///
+/// **format specs like `{:>8}` or `{:?}` and positional or named arguments like `{0}` or `{name}`
+/// are not supported and do not compile, format such arguments with `format!` first**
+///
+/// The macro expands into a `StyledFormat` that borrows the arguments like `format_args!` does,
+/// so it is meant to be used right away in `format!`, `println!` or `to_string`.
+///
+/// # Example:
+/// ```
+/// use term_tools::styled;
+/// let msg = "disk full";
+/// let line = styled!("{bold red}Error:{/} {}", msg).to_string();
+/// assert_eq!(line, "\x1b[1;31mError:\x1b[0m disk full");
+/// ```
+///
+/// Unknown tags, a `{/}` without an open tag, tags that are never closed and a wrong number
+/// of arguments do not compile:
+/// ```compile_fail
+/// use term_tools::styled;
+/// let line = styled!("{bold rde}Error:{/} {}", "disk full").to_string();
+/// ```
+/// ```compile_fail
+/// use term_tools::styled;
+/// let line = styled!("{bold}{:>8}{/}", 42).to_string();
+/// ```
+#[macro_export]
+macro_rules! styled {
+    ($format:literal $(, $arg:expr)* $(,)?) => {
+        $crate::styled_format::StyledFormat::new(
+            {
+                const _: () = $crate::styled_format::check($format, <[&str]>::len(&[$(stringify!($arg)),*]));
+                $format
+            },
+            &[$(&$arg),*]
+        )
+    };
+}
+
+/// The tags of the `styled!` macro with their styles.
+/// Returns `true` if the style is a color.
+const fn is_color(style: &Styles) -> bool {
+    matches!(style, Styles::StyleBasicColor(_) | Styles::StylePaletteColor(_) | Styles::StyleRgb(_))
+}
+
+/// Checks the tag between `start` and `end`, every word must be a known tag
+/// and `on` must be followed by a color.
+///
+/// Words are split at ASCII whitespace, like `StyledFormat` splits them when painting.
+const fn check_tag(bytes: &[u8], start: usize, end: usize) {
+    let mut i = start;
+    let mut background = false;
+    let mut words = 0;
+    while i < end {
+        while i < end && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let word = i;
+        while i < end && !bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if word == i {
+            break;
+        }
+        words += 1;
+        if i - word == 2 && bytes[word] == b'o' && bytes[word + 1] == b'n' {
+            if background {
+                panic!("`on` must be followed by a color in the styled! format string");
+            }
+            background = true;
+            continue;
+        }
+        match find_name(bytes, word, i) {
+            Some(style) => {
+                if background && !is_color(&style) {
+                    panic!("`on` must be followed by a color in the styled! format string");
+                }
+            }
+            None => panic!("unknown tag in the styled! format string"),
+        }
+        background = false;
+    }
+    if background {
+        panic!("`on` must be followed by a color in the styled! format string");
+    }
+    if words == 0 {
+        panic!("empty tag in the styled! format string, use `{{}}` for a placeholder");
+    }
+}
+
+/// Returns `true` if the bytes between `start` and `end` are only digits.
+const fn is_number(bytes: &[u8], start: usize, end: usize) -> bool {
+    let mut i = start;
+    while i < end {
+        if !bytes[i].is_ascii_digit() {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Returns `true` if the bytes between `start` and `end` are one identifier, like a named argument.
+const fn is_identifier(bytes: &[u8], start: usize, end: usize) -> bool {
+    if bytes[start].is_ascii_digit() {
+        return false;
+    }
+    let mut i = start;
+    while i < end {
+        if !bytes[i].is_ascii_alphanumeric() && bytes[i] != b'_' {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Returns `true` if there is a `:` between `start` and `end`, like in a format spec.
+const fn has_colon(bytes: &[u8], start: usize, end: usize) -> bool {
+    let mut i = start;
+    while i < end {
+        if bytes[i] == b':' {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Checks the format string of the `styled!` macro while compiling.
+///
+/// The tags must be known and balanced and the number of `{}` placeholders must be `args`.
+/// Format specs and positional or named arguments are rejected with their own message.
+#[doc(hidden)]
+pub const fn check(format: &str, args: usize) {
+    let bytes = format.as_bytes();
+    let mut i = 0;
+    let mut placeholders = 0;
+    let mut open = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'}' {
+            if i + 1 < bytes.len() && bytes[i + 1] == b'}' {
+                i += 2;
+                continue;
+            }
+            panic!("unmatched `}}` in the styled! format string, use `}}}}` for a literal `}}`");
+        }
+        if bytes[i] != b'{' {
+            i += 1;
+            continue;
+        }
+        if i + 1 < bytes.len() && bytes[i + 1] == b'{' {
+            i += 2;
+            continue;
+        }
+        let start = i + 1;
+        let mut end = start;
+        while end < bytes.len() && bytes[end] != b'}' {
+            end += 1;
+        }
+        if end == bytes.len() {
+            panic!("unclosed `{{` in the styled! format string, use `{{{{` for a literal `{{`");
+        }
+        if end == start {
+            placeholders += 1;
+        } else if has_colon(bytes, start, end) {
+            panic!("format specs like `{{:>8}}` or `{{:?}}` are not supported in the styled! format string, use `{{}}`");
+        } else if is_number(bytes, start, end) {
+            panic!("positional arguments like `{{0}}` are not supported in the styled! format string, use `{{}}`");
+        } else if is_identifier(bytes, start, end) && find_name(bytes, start, end).is_none() {
+            panic!(
+                "unknown tag in the styled! format string, named arguments like `{{name}}` are not supported, use `{{}}`"
+            );
+        } else if end == start + 1 && bytes[start] == b'/' {
+            if open == 0 {
+                panic!("`{{/}}` closes no tag in the styled! format string");
+            }
+            open -= 1;
+        } else {
+            check_tag(bytes, start, end);
+            open += 1;
+        }
+        i = end + 1;
+    }
+    if open != 0 {
+        panic!("a tag in the styled! format string is never closed with `{{/}}`");
+    }
+    if placeholders != args {
+        panic!("the number of `{{}}` placeholders in the styled! format string does not match the arguments");
+    }
+}
+
+/// A struct representing a format string with styles and its arguments,
+/// created by the `styled!` macro.
+pub struct StyledFormat<'a> {
+    format: &'static str,
+    args: &'a [&'a dyn fmt::Display],
+    color_level: ColorLevel,
+}
+
+impl<'a> StyledFormat<'a> {
+    /// Returns the format string with its arguments, use the `styled!` macro instead.
+    #[doc(hidden)]
+    pub fn new(format: &'static str, args: &'a [&'a dyn fmt::Display]) -> Self {
+        StyledFormat { format, args, color_level: ColorLevel::default() }
+    }
+
+    /// Sets the color level that the colors are downsampled to, see [`crate::StyledText::color_level`].
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::{ styled, ColorLevel };
+    /// let line = styled!("{bold red}Error{/}").color_level(ColorLevel::NoColor).to_string();
+    /// assert_eq!(line, "Error");
+    /// ```
+    pub fn color_level(mut self, level: ColorLevel) -> Self {
+        self.color_level = level;
+        self
+    }
+
+    /// Returns the style of the tag on top of the given style.
+    fn tag_style(&self, tag: &str, parent: &SpanStyle) -> SpanStyle {
+        let mut style = *parent;
+        if self.color_level == ColorLevel::NoColor {
+            return style;
+        }
+        let mut paint_type = PaintType::FG;
+        for word in tag.split_ascii_whitespace() {
+            if word == "on" {
+                paint_type = PaintType::BG;
+                continue;
+            }
+            let found = find_name(word.as_bytes(), 0, word.len());
+            if let Some(s) = found.and_then(|s| s.downsample_as(self.color_level, &paint_type)) {
+                style.apply(&s, &paint_type);
+            }
+            paint_type = PaintType::FG;
+        }
+        style
+    }
+}
+
+/// Writes the codes that change the `written` style into the `target` style.
+fn write_transition(f: &mut fmt::Formatter<'_>, written: &mut SpanStyle, target: &SpanStyle) -> fmt::Result {
+    let codes = written.transition_to(target);
+    if !codes.is_empty() {
+        f.write_str(&ANSIEscapeCode::new(&codes.join(";")).code())?;
+    }
+    *written = *target;
+    Ok(())
+}
+
+impl fmt::Display for StyledFormat<'_> {
+    /// Writes the format string with the arguments, each piece of text gets the style of its tags.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut styles = vec![SpanStyle::default()];
+        let mut written = SpanStyle::default();
+        let mut args = self.args.iter();
+        let mut rest = self.format;
+        while !rest.is_empty() {
+            let current = styles[styles.len() - 1];
+            let end = rest.find(['{', '}']).unwrap_or(rest.len());
+            if end > 0 {
+                write_transition(f, &mut written, &current)?;
+                f.write_str(&rest[..end])?;
+                rest = &rest[end..];
+                continue;
+            }
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                write_transition(f, &mut written, &current)?;
+                f.write_str(&rest[..1])?;
+                rest = &rest[2..];
+                continue;
+            }
+            // the macro rejects a lone brace, `new` alone writes it as it is
+            let close = match rest.find('}') {
+                Some(close) if rest.starts_with('{') => close,
+                _ => {
+                    write_transition(f, &mut written, &current)?;
+                    f.write_str(&rest[..1])?;
+                    rest = &rest[1..];
+                    continue;
+                }
+            };
+            let tag = &rest[1..close];
+            rest = &rest[close + 1..];
+            match tag {
+                "" => {
+                    if let Some(arg) = args.next() {
+                        write_transition(f, &mut written, &current)?;
+                        write!(f, "{}", arg)?;
+                    }
+                }
+                "/" => {
+                    if styles.len() > 1 {
+                        styles.pop();
+                    }
+                }
+                _ => styles.push(self.tag_style(tag, &current)),
+            }
+        }
+        if !written.is_plain() {
+            f.write_str(&ANSIEscapeCode::new("0").code())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ColorLevel;
+
+    #[test]
+    fn format_with_tags() {
+        assert_eq!(styled!("{bold red}Error:{/} {}", "oops").to_string(), "\x1b[1;31mError:\x1b[0m oops");
+        assert_eq!(styled!("{on blue}a{/}").to_string(), "\x1b[44ma\x1b[0m");
+        assert_eq!(styled!("{red on white}a{/}b").to_string(), "\x1b[31;47ma\x1b[0mb");
+        assert_eq!(styled!("{b grey}a{/}").to_string(), "\x1b[1;90ma\x1b[0m");
+    }
+
+    #[test]
+    fn format_nested_tags() {
+        let line = styled!("{red}a{bold}{}{/}c{/}", 1).to_string();
+        assert_eq!(line, "\x1b[31ma\x1b[1m1\x1b[22mc\x1b[0m");
+    }
+
+    #[test]
+    fn format_without_tags() {
+        assert_eq!(styled!("plain {} and {}", 1, 'x').to_string(), "plain 1 and x");
+        assert_eq!(styled!("{{literal}}").to_string(), "{literal}");
+        assert_eq!(styled!("").to_string(), "");
+    }
+
+    #[test]
+    fn format_lone_braces_without_check() {
+        let line = super::StyledFormat::new("{red}a}b{", &[]).to_string();
+        assert_eq!(line, "\x1b[31ma}b{\x1b[0m");
+    }
+
+    #[test]
+    fn format_with_color_level() {
+        let line = styled!("{bold red}a{/}").color_level(ColorLevel::NoColor).to_string();
+        assert_eq!(line, "a");
+    }
+
+    #[test]
+    fn check_format_strings() {
+        super::check("{bold red on white}{}{/}", 1);
+        super::check("{{}}{curly_underline}{{{/}", 0);
+        super::check("{bold\ton  red}{/}", 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown tag")]
+    fn check_unknown_tag() {
+        super::check("{bold rde}a{/}", 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "format specs like `{:>8}` or `{:?}` are not supported")]
+    fn check_format_spec() {
+        super::check("{bold}{:>8}{/}", 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "positional arguments like `{0}` are not supported")]
+    fn check_positional_argument() {
+        super::check("{0}", 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "named arguments like `{name}` are not supported")]
+    fn check_named_argument() {
+        super::check("{name}", 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "empty tag")]
+    fn check_empty_tag() {
+        super::check("{ }a{/}", 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "`on` must be followed by a color")]
+    fn check_on_without_color() {
+        super::check("{on bold}a{/}", 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "never closed")]
+    fn check_unclosed_tag() {
+        super::check("{bold}a", 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "closes no tag")]
+    fn check_unexpected_close() {
+        super::check("a{/}", 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match the arguments")]
+    fn check_placeholder_count() {
+        super::check("{} {}", 1);
+    }
+}
@@ -1,16 +1,20 @@
-/// A module for working with styles and colors.
-///
-/// This module provides a set of types and traits for representing
-///  different styles and colors,
-/// as well as a way to generate styles based on a given paint type.
+//! A module for working with styles and colors.
+//!
+//! This module provides a set of types and traits for representing
+//!  different styles and colors,
+//! as well as a way to generate styles based on a given paint type.
 
 // =======================================================================
 
+use std::fmt;
+
 use basic_color::BasicColor;
+use color_level::ColorLevel;
 use formatter::Formatter;
 use paint_type::PaintType;
 use palette::PaletteColor;
 use rgb::Rgb;
+use underline_style::UnderlineStyle;
 
 // =======================================================================
 pub mod paint_type;
@@ -18,14 +22,27 @@ pub mod formatter;
 pub mod basic_color;
 pub mod rgb;
 pub mod palette;
+pub mod color_level;
+pub mod color;
+pub mod span_style;
+pub mod reset_mode;
+pub mod sgr_mode;
+pub mod underline_style;
+pub mod css_color;
+pub mod color_spec;
+pub mod color_space;
+pub mod color_ops;
+pub mod color_scheme;
 
 // =======================================================================
 
 /// An enum representing different styles.
 ///
-/// This enum has five variants, each representing a different type of style:
-/// `StyleRgb`, `StyleBasicColor`, `StylePaletteColor`, `StylePaintType`, and `StyleFormatter`.
-#[derive(Debug, Clone)]
+/// This enum has six variants, each representing a different type of style:
+/// `StyleRgb`, `StyleBasicColor`, `StylePaletteColor`, `StylePaintType`, `StyleFormatter`
+/// and `StyleUnderline`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(clippy::enum_variant_names)]
 pub enum Styles {
     /// A style represented by an RGB color.
     StyleRgb(Rgb),
@@ -37,6 +54,8 @@ pub enum Styles {
     StylePaintType(PaintType),
     /// A style represented by a formatter.
     StyleFormatter(Formatter),
+    /// A style represented by an underline style.
+    StyleUnderline(UnderlineStyle),
 }
 
 impl Styles {
@@ -51,10 +70,159 @@ impl Styles {
             Styles::StylePaletteColor(c) => c.make_styles(paint_type),
             Styles::StyleRgb(c) => c.make_styles(paint_type),
             Styles::StyleFormatter(c) => c.make_styles(paint_type),
+            Styles::StyleUnderline(c) => c.make_styles(paint_type),
+        }
+    }
+
+    /// Writes the styles based on the given paint type into `f`, see [`Stylify::write_styles`].
+    pub fn write_styles<W: fmt::Write>(&self, f: &mut W, paint_type: Option<&PaintType>) -> fmt::Result {
+        match self {
+            Styles::StyleBasicColor(c) => c.write_styles(f, paint_type),
+            Styles::StylePaintType(c) => c.write_styles(f, paint_type),
+            Styles::StylePaletteColor(c) => c.write_styles(f, paint_type),
+            Styles::StyleRgb(c) => c.write_styles(f, paint_type),
+            Styles::StyleFormatter(c) => c.write_styles(f, paint_type),
+            Styles::StyleUnderline(c) => c.write_styles(f, paint_type),
+        }
+    }
+
+    /// Converts the style into one that a terminal with the given color level can show.
+    ///
+    /// `Rgb` colors become palette colors on `Ansi256` and basic colors on `Ansi16`,
+    /// palette colors become basic colors on `Ansi16`.
+    /// On `NoColor` all colors are dropped and `None` is returned for them.
+    /// Underline styles are kept together with underline colors: both are extensions that
+    /// terminals with 256 colors and more understand, so they are kept from `Ansi256` on
+    /// and on `Ansi16` an underline style becomes a plain underline, see `downsample_as`.
+    pub fn downsample(&self, level: ColorLevel) -> Option<Styles> {
+        match (self, level) {
+            (Styles::StyleUnderline(u), ColorLevel::Ansi16) => {
+                Some(Styles::StyleFormatter(u.fallback()))
+            }
+            (
+                Styles::StyleRgb(_) | Styles::StyleBasicColor(_) | Styles::StylePaletteColor(_),
+                ColorLevel::NoColor,
+            ) => None,
+            (Styles::StyleRgb(c), ColorLevel::Ansi256) => {
+                Some(Styles::StylePaletteColor(c.to_palette()))
+            }
+            (Styles::StyleRgb(c), ColorLevel::Ansi16) => Some(Styles::StyleBasicColor(c.to_basic())),
+            (Styles::StylePaletteColor(c), ColorLevel::Ansi16) => {
+                Some(Styles::StyleBasicColor(c.to_basic()))
+            }
+            _ => Some(*self),
+        }
+    }
+
+    /// Converts the style into one that a terminal with the given color level can show,
+    /// when it is painted with the given paint type.
+    ///
+    /// This works like `downsample`, but underline colors are dropped below `Ansi256`,
+    /// because there is no 16 color code for them.
+    pub fn downsample_as(&self, level: ColorLevel, paint_type: &PaintType) -> Option<Styles> {
+        let is_color = matches!(
+            self,
+            Styles::StyleRgb(_) | Styles::StyleBasicColor(_) | Styles::StylePaletteColor(_)
+        );
+        if is_color && *paint_type == PaintType::UL && level < ColorLevel::Ansi256 {
+            return None;
         }
+        self.downsample(level)
     }
 }
 
+/// The names of the styles, used for the tags of `styled!`, by `Style::from_str`
+/// and for the readable names of formatters and colors.
+///
+/// The first name of a style is its readable name, the constant of the style in lowercase,
+/// the short aliases like `b` or `grey` come after all readable names.
+pub(crate) const NAMES: [(&str, Styles); 58] = [
+    ("reset", formatter::RESET),
+    ("bold", formatter::BOLD),
+    ("faint", formatter::FAINT),
+    ("italic", formatter::ITALIC),
+    ("underline", formatter::UNDERLINE),
+    ("slow_blink", formatter::SLOW_BLINK),
+    ("rapid_blink", formatter::RAPID_BLINK),
+    ("inverse", formatter::INVERSE),
+    ("conceal", formatter::CONCEAL),
+    ("strikethrough", formatter::STRIKETHROUGH),
+    ("double_underline", formatter::DOUBLE_UNDERLINE),
+    ("proportional_spacing", formatter::PROPORTIONAL_SPACING),
+    ("framed", formatter::FRAMED),
+    ("encircled", formatter::ENCIRCLED),
+    ("overline", formatter::OVERLINE),
+    ("superscript", formatter::SUPERSCRIPT),
+    ("subscript", formatter::SUBSCRIPT),
+    ("curly_underline", underline_style::CURLY_UNDERLINE),
+    ("dotted_underline", underline_style::DOTTED_UNDERLINE),
+    ("dashed_underline", underline_style::DASHED_UNDERLINE),
+    ("black", basic_color::BLACK),
+    ("red", basic_color::RED),
+    ("green", basic_color::GREEN),
+    ("yellow", basic_color::YELLOW),
+    ("blue", basic_color::BLUE),
+    ("magenta", basic_color::MAGENTA),
+    ("cyan", basic_color::CYAN),
+    ("white", basic_color::WHITE),
+    ("gray", basic_color::GRAY),
+    ("bright_red", basic_color::BRIGHT_RED),
+    ("bright_green", basic_color::BRIGHT_GREEN),
+    ("bright_yellow", basic_color::BRIGHT_YELLOW),
+    ("bright_blue", basic_color::BRIGHT_BLUE),
+    ("bright_magenta", basic_color::BRIGHT_MAGENTA),
+    ("bright_cyan", basic_color::BRIGHT_CYAN),
+    ("bright_white", basic_color::BRIGHT_WHITE),
+    ("normal_intensity", formatter::NORMAL_INTENSITY),
+    ("no_italic", formatter::NO_ITALIC),
+    ("no_underline", formatter::NO_UNDERLINE),
+    ("no_blink", formatter::NO_BLINK),
+    ("no_inverse", formatter::NO_INVERSE),
+    ("reveal", formatter::REVEAL),
+    ("no_strikethrough", formatter::NO_STRIKETHROUGH),
+    ("no_proportional_spacing", formatter::NO_PROPORTIONAL_SPACING),
+    ("no_framed", formatter::NO_FRAMED),
+    ("no_overline", formatter::NO_OVERLINE),
+    ("no_script", formatter::NO_SCRIPT),
+    ("b", formatter::BOLD),
+    ("dim", formatter::FAINT),
+    ("i", formatter::ITALIC),
+    ("u", formatter::UNDERLINE),
+    ("uu", formatter::DOUBLE_UNDERLINE),
+    ("blink", formatter::SLOW_BLINK),
+    ("reverse", formatter::INVERSE),
+    ("hidden", formatter::CONCEAL),
+    ("strike", formatter::STRIKETHROUGH),
+    ("s", formatter::STRIKETHROUGH),
+    ("grey", basic_color::GRAY),
+];
+
+/// Returns the style of the name between `start` and `end` of `bytes`, or `None` if it is not known.
+///
+/// This is a `const fn`, so `styled!` can check its tags while compiling.
+pub(crate) const fn find_name(bytes: &[u8], start: usize, end: usize) -> Option<Styles> {
+    let mut t = 0;
+    while t < NAMES.len() {
+        let name = NAMES[t].0.as_bytes();
+        if name.len() == end - start {
+            let mut i = 0;
+            while i < name.len() && name[i] == bytes[start + i] {
+                i += 1;
+            }
+            if i == name.len() {
+                return Some(NAMES[t].1);
+            }
+        }
+        t += 1;
+    }
+    None
+}
+
+/// Returns the readable name of the style, or `None` if it has no name.
+pub(crate) fn name_of(style: &Styles) -> Option<&'static str> {
+    NAMES.iter().find(|(_, s)| s == style).map(|(name, _)| *name)
+}
+
 /// A trait for types that can generate styles based on a given paint type.
 pub trait Stylify {
     /// Generates String styles based on the given paint type.
@@ -62,6 +230,14 @@ pub trait Stylify {
     /// This method takes an optional `paint_type` parameter, which is used to determine the styles to generate.
     /// It returns a string representing the generated styles.
     fn make_styles(&self, paint_type: Option<&PaintType>) -> String;
+
+    /// Writes the styles based on the given paint type into `f`.
+    ///
+    /// This method does the same as `make_styles` but writes straight into the writer,
+    /// so no `String` has to be allocated for the styles.
+    fn write_styles<W: fmt::Write>(&self, f: &mut W, paint_type: Option<&PaintType>) -> fmt::Result {
+        f.write_str(&self.make_styles(paint_type))
+    }
 }
 
 #[cfg(test)]
@@ -114,4 +290,95 @@ mod tests {
         assert_eq!(styles_fg, "3");
         assert_eq!(styles_bg, "3");
     }
+
+    #[test]
+    fn test_write_styles() {
+        let styles = [
+            Styles::StyleRgb(Rgb { r: 255, g: 0, b: 0 }),
+            Styles::StyleBasicColor(BasicColor { fg: 34, bg: 44 }),
+            Styles::StylePaletteColor(PaletteColor { index: 44 }),
+            Styles::StylePaintType(PaintType::BG),
+            Styles::StyleFormatter(Formatter { code: 3 }),
+        ];
+        for style in styles {
+            for paint_type in [None, Some(&PaintType::FG), Some(&PaintType::BG)] {
+                let mut written = String::new();
+                style.write_styles(&mut written, paint_type).unwrap();
+                assert_eq!(written, style.make_styles(paint_type));
+            }
+        }
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(find_name(b"grey", 0, 4), Some(basic_color::GRAY));
+        assert_eq!(find_name(b"on b", 3, 4), Some(formatter::BOLD));
+        assert_eq!(find_name(b"rde", 0, 3), None);
+        assert_eq!(name_of(&basic_color::GRAY), Some("gray"));
+        assert_eq!(name_of(&formatter::STRIKETHROUGH), Some("strikethrough"));
+        assert_eq!(name_of(&Styles::StyleUnderline(UnderlineStyle::Dashed)), Some("dashed_underline"));
+        assert_eq!(name_of(&Styles::StyleFormatter(Formatter { code: 60 })), None);
+        for (i, (name, style)) in NAMES.iter().enumerate() {
+            assert_eq!(find_name(name.as_bytes(), 0, name.len()), Some(*style));
+            assert!(NAMES[..i].iter().all(|(n, _)| n != name), "{name} is in NAMES twice");
+        }
+    }
+
+    #[test]
+    fn test_downsample_rgb() {
+        let style = Styles::StyleRgb(Rgb { r: 255, g: 0, b: 0 });
+        assert_eq!(style.downsample(ColorLevel::TrueColor), Some(style));
+        assert_eq!(
+            style.downsample(ColorLevel::Ansi256),
+            Some(Styles::StylePaletteColor(PaletteColor { index: 196 }))
+        );
+        assert_eq!(
+            style.downsample(ColorLevel::Ansi16),
+            Some(Styles::StyleBasicColor(BasicColor { fg: 91, bg: 101 }))
+        );
+        assert_eq!(style.downsample(ColorLevel::NoColor), None);
+    }
+
+    #[test]
+    fn test_downsample_palette() {
+        let style = Styles::StylePaletteColor(PaletteColor { index: 21 });
+        assert_eq!(style.downsample(ColorLevel::TrueColor), Some(style));
+        assert_eq!(style.downsample(ColorLevel::Ansi256), Some(style));
+        assert_eq!(
+            style.downsample(ColorLevel::Ansi16),
+            Some(Styles::StyleBasicColor(BasicColor { fg: 34, bg: 44 }))
+        );
+        assert_eq!(style.downsample(ColorLevel::NoColor), None);
+    }
+
+    #[test]
+    fn test_downsample_keeps_formatters() {
+        let style = Styles::StyleFormatter(Formatter { code: 1 });
+        assert_eq!(style.downsample(ColorLevel::Ansi16), Some(style));
+        let style = Styles::StyleBasicColor(BasicColor { fg: 31, bg: 41 });
+        assert_eq!(style.downsample(ColorLevel::Ansi16), Some(style));
+        assert_eq!(style.downsample(ColorLevel::NoColor), None);
+    }
+
+    #[test]
+    fn test_downsample_underline_style() {
+        let style = Styles::StyleUnderline(UnderlineStyle::Curly);
+        assert_eq!(style.downsample(ColorLevel::TrueColor), Some(style));
+        assert_eq!(style.downsample(ColorLevel::Ansi256), Some(style));
+        assert_eq!(style.downsample(ColorLevel::Ansi16), Some(Styles::StyleFormatter(Formatter { code: 4 })));
+    }
+
+    #[test]
+    fn test_downsample_underline_color() {
+        let style = Styles::StyleRgb(Rgb { r: 255, g: 0, b: 0 });
+        assert_eq!(
+            style.downsample_as(ColorLevel::Ansi256, &PaintType::UL),
+            Some(Styles::StylePaletteColor(PaletteColor { index: 196 }))
+        );
+        assert_eq!(style.downsample_as(ColorLevel::Ansi16, &PaintType::UL), None);
+        assert_eq!(
+            style.downsample_as(ColorLevel::Ansi16, &PaintType::FG),
+            Some(Styles::StyleBasicColor(BasicColor { fg: 91, bg: 101 }))
+        );
+    }
 }
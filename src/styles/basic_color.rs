@@ -1,9 +1,11 @@
-use super::{ paint_type::PaintType, Styles, Stylify };
+use std::fmt;
+
+use super::{ name_of, paint_type::PaintType, rgb::Rgb, Styles, Stylify };
 
 // =======================================================================
 
 /// A struct representing a basic color with foreground and background values.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct BasicColor {
     /// The foreground color value.
     pub fg: u8,
@@ -15,12 +17,24 @@ impl Stylify for BasicColor {
     /// Returns a string representation of the color style.
     ///
     /// If `paint_type` is `None`, the foreground color is assumed.
+    /// There is no basic underline color code, so as underline color the palette index
+    /// of the color is written, like `58;5;1` for red.
     fn make_styles(&self, paint_type: Option<&PaintType>) -> String {
         let paint_type = paint_type.unwrap_or(&PaintType::FG);
-        format!("{}", match paint_type {
-            PaintType::FG => self.fg,
-            PaintType::BG => self.bg,
-        })
+        match paint_type {
+            PaintType::FG => format!("{}", self.fg),
+            PaintType::BG => format!("{}", self.bg),
+            PaintType::UL => format!("58;5;{}", self.index().unwrap_or(0)),
+        }
+    }
+
+    /// Writes the color style into `f` without allocating.
+    fn write_styles<W: fmt::Write>(&self, f: &mut W, paint_type: Option<&PaintType>) -> fmt::Result {
+        match paint_type.unwrap_or(&PaintType::FG) {
+            PaintType::FG => write!(f, "{}", self.fg),
+            PaintType::BG => write!(f, "{}", self.bg),
+            PaintType::UL => write!(f, "58;5;{}", self.index().unwrap_or(0)),
+        }
     }
 }
 
@@ -48,6 +62,59 @@ color_code!(BRIGHT_MAGENTA,{fg:95,bg:105});
 color_code!(BRIGHT_CYAN,{fg:96,bg:106});
 color_code!(BRIGHT_WHITE,{fg:97,bg:107});
 
+/// The 16 basic colors in the order of their palette index,
+/// together with the rgb value that xterm shows for each of them.
+pub(crate) const BASIC_COLORS: [(BasicColor, Rgb); 16] = [
+    (BasicColor { fg: 30, bg: 40 }, Rgb { r: 0, g: 0, b: 0 }),
+    (BasicColor { fg: 31, bg: 41 }, Rgb { r: 205, g: 0, b: 0 }),
+    (BasicColor { fg: 32, bg: 42 }, Rgb { r: 0, g: 205, b: 0 }),
+    (BasicColor { fg: 33, bg: 43 }, Rgb { r: 205, g: 205, b: 0 }),
+    (BasicColor { fg: 34, bg: 44 }, Rgb { r: 0, g: 0, b: 238 }),
+    (BasicColor { fg: 35, bg: 45 }, Rgb { r: 205, g: 0, b: 205 }),
+    (BasicColor { fg: 36, bg: 46 }, Rgb { r: 0, g: 205, b: 205 }),
+    (BasicColor { fg: 37, bg: 47 }, Rgb { r: 229, g: 229, b: 229 }),
+    (BasicColor { fg: 90, bg: 100 }, Rgb { r: 127, g: 127, b: 127 }),
+    (BasicColor { fg: 91, bg: 101 }, Rgb { r: 255, g: 0, b: 0 }),
+    (BasicColor { fg: 92, bg: 102 }, Rgb { r: 0, g: 255, b: 0 }),
+    (BasicColor { fg: 93, bg: 103 }, Rgb { r: 255, g: 255, b: 0 }),
+    (BasicColor { fg: 94, bg: 104 }, Rgb { r: 92, g: 92, b: 255 }),
+    (BasicColor { fg: 95, bg: 105 }, Rgb { r: 255, g: 0, b: 255 }),
+    (BasicColor { fg: 96, bg: 106 }, Rgb { r: 0, g: 255, b: 255 }),
+    (BasicColor { fg: 97, bg: 107 }, Rgb { r: 255, g: 255, b: 255 }),
+];
+
+impl BasicColor {
+    /// Returns the basic color of the given palette index.
+    ///
+    /// Only the indexes 0 to 15 are basic colors, for other indexes `None` is returned.
+    pub fn from_index(index: u8) -> Option<BasicColor> {
+        BASIC_COLORS.get(index as usize).map(|(c, _)| *c)
+    }
+
+    /// Returns the palette index (0 to 15) of the basic color.
+    ///
+    /// If the color is not one of the 16 basic colors `None` is returned.
+    pub fn index(&self) -> Option<u8> {
+        BASIC_COLORS.iter()
+            .position(|(c, _)| c.fg == self.fg)
+            .map(|i| i as u8)
+    }
+
+    /// Returns the name of the basic color like its constant in lowercase, like `bright_red`.
+    ///
+    /// If the color is not one of the 16 basic colors `None` is returned.
+    pub fn name(&self) -> Option<&'static str> {
+        name_of(&Styles::StyleBasicColor(*self))
+    }
+
+    /// Returns the rgb value that xterm shows for the basic color.
+    ///
+    /// If the color is not one of the 16 basic colors `None` is returned.
+    pub fn to_rgb(&self) -> Option<Rgb> {
+        self.index().map(|i| BASIC_COLORS[i as usize].1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,6 +140,13 @@ mod tests {
         assert_eq!(styles, "40")
     }
 
+    #[test]
+    fn test_make_style_ul() {
+        let color = BasicColor { fg: 31, bg: 41 };
+        let styles = color.make_styles(Some(&PaintType::UL));
+        assert_eq!(styles, "58;5;1")
+    }
+
     #[test]
     fn test_fg_and_bg_values() {
         let color = BasicColor { fg: 30, bg: 40 };
@@ -103,6 +177,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_from_index() {
+        assert_eq!(BasicColor::from_index(1), Some(BasicColor { fg: 31, bg: 41 }));
+        assert_eq!(BasicColor::from_index(8), Some(BasicColor { fg: 90, bg: 100 }));
+        assert_eq!(BasicColor::from_index(15), Some(BasicColor { fg: 97, bg: 107 }));
+        assert_eq!(BasicColor::from_index(16), None);
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(BasicColor { fg: 31, bg: 41 }.name(), Some("red"));
+        assert_eq!(BasicColor { fg: 97, bg: 107 }.name(), Some("bright_white"));
+        assert_eq!(BasicColor { fg: 100, bg: 200 }.name(), None);
+    }
+
+    #[test]
+    fn test_index_and_rgb() {
+        let color = BasicColor { fg: 94, bg: 104 };
+        assert_eq!(color.index(), Some(12));
+        assert_eq!(color.to_rgb(), Some(Rgb { r: 92, g: 92, b: 255 }));
+
+        let color = BasicColor { fg: 100, bg: 200 };
+        assert_eq!(color.index(), None);
+        assert_eq!(color.to_rgb(), None);
+    }
+
     macro_rules! color_test {
         ($test_name:ident, $color_name:ident, $color:expr, $fg:expr, $bg:expr) => {
             #[test]
@@ -0,0 +1,137 @@
+//! A module for working with any kind of color.
+//!
+//! This module provides an enum `Color` that holds one of the color types of the crate:
+//! `BasicColor`, `PaletteColor` or `Rgb`.
+//! It also implements the `Stylify` trait for `Color`,
+//! which allows for generating styles based on the paint type.
+
+// =======================================================================
+
+use std::fmt;
+
+use super::{
+    basic_color::BasicColor,
+    color_level::ColorLevel,
+    paint_type::PaintType,
+    palette::PaletteColor,
+    rgb::Rgb,
+    Styles,
+    Stylify,
+};
+
+// =======================================================================
+
+/// An enum representing a color of any kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    /// One of the 16 basic colors.
+    Basic(BasicColor),
+    /// A color of the 256 color palette.
+    Palette(PaletteColor),
+    /// A 24 bit rgb color.
+    Rgb(Rgb),
+}
+
+impl Color {
+    /// Returns the color of a style, or `None` if the style is not a color.
+    pub fn from_styles(style: &Styles) -> Option<Color> {
+        match style {
+            Styles::StyleBasicColor(c) => Some(Color::Basic(*c)),
+            Styles::StylePaletteColor(c) => Some(Color::Palette(*c)),
+            Styles::StyleRgb(c) => Some(Color::Rgb(*c)),
+            _ => None,
+        }
+    }
+
+    /// Returns the rgb value of the color as xterm shows it.
+    ///
+    /// Basic colors that are not one of the 16 known colors are shown as black.
+    pub fn to_rgb(&self) -> Rgb {
+        match self {
+            Color::Basic(c) => c.to_rgb().unwrap_or(Rgb { r: 0, g: 0, b: 0 }),
+            Color::Palette(c) => c.to_rgb(),
+            Color::Rgb(c) => *c,
+        }
+    }
+
+    /// Converts the color into one that a terminal with the given color level can show.
+    ///
+    /// On `NoColor` `None` is returned.
+    pub fn downsample(&self, level: ColorLevel) -> Option<Color> {
+        Styles::from(*self)
+            .downsample(level)
+            .and_then(|s| Color::from_styles(&s))
+    }
+}
+
+impl From<Color> for Styles {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::Basic(c) => Styles::StyleBasicColor(c),
+            Color::Palette(c) => Styles::StylePaletteColor(c),
+            Color::Rgb(c) => Styles::StyleRgb(c),
+        }
+    }
+}
+
+impl Stylify for Color {
+    /// Returns a string representation of the color style.
+    ///
+    /// If `paint_type` is `None`, the foreground color is assumed.
+    fn make_styles(&self, paint_type: Option<&PaintType>) -> String {
+        match self {
+            Color::Basic(c) => c.make_styles(paint_type),
+            Color::Palette(c) => c.make_styles(paint_type),
+            Color::Rgb(c) => c.make_styles(paint_type),
+        }
+    }
+
+    /// Writes the color style into `f` without allocating.
+    fn write_styles<W: fmt::Write>(&self, f: &mut W, paint_type: Option<&PaintType>) -> fmt::Result {
+        match self {
+            Color::Basic(c) => c.write_styles(f, paint_type),
+            Color::Palette(c) => c.write_styles(f, paint_type),
+            Color::Rgb(c) => c.write_styles(f, paint_type),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styles::formatter::Formatter;
+
+    #[test]
+    fn test_from_styles() {
+        let style = Styles::StyleRgb(Rgb { r: 1, g: 2, b: 3 });
+        assert_eq!(Color::from_styles(&style), Some(Color::Rgb(Rgb { r: 1, g: 2, b: 3 })));
+        let style = Styles::StylePaletteColor(PaletteColor { index: 9 });
+        assert_eq!(Color::from_styles(&style), Some(Color::Palette(PaletteColor { index: 9 })));
+        let style = Styles::StyleFormatter(Formatter { code: 1 });
+        assert_eq!(Color::from_styles(&style), None);
+    }
+
+    #[test]
+    fn test_make_styles() {
+        let color = Color::Basic(BasicColor { fg: 31, bg: 41 });
+        assert_eq!(color.make_styles(None), "31");
+        assert_eq!(color.make_styles(Some(&PaintType::BG)), "41");
+        let color = Color::Palette(PaletteColor { index: 200 });
+        assert_eq!(color.make_styles(Some(&PaintType::BG)), "48;5;200");
+        let color = Color::Rgb(Rgb { r: 1, g: 2, b: 3 });
+        assert_eq!(color.make_styles(Some(&PaintType::FG)), "38;2;1;2;3");
+    }
+
+    #[test]
+    fn test_to_rgb() {
+        assert_eq!(Color::Basic(BasicColor { fg: 31, bg: 41 }).to_rgb(), Rgb { r: 205, g: 0, b: 0 });
+        assert_eq!(Color::Palette(PaletteColor { index: 196 }).to_rgb(), Rgb { r: 255, g: 0, b: 0 });
+    }
+
+    #[test]
+    fn test_downsample() {
+        let color = Color::Rgb(Rgb { r: 255, g: 0, b: 0 });
+        assert_eq!(color.downsample(ColorLevel::Ansi256), Some(Color::Palette(PaletteColor { index: 196 })));
+        assert_eq!(color.downsample(ColorLevel::NoColor), None);
+    }
+}
@@ -0,0 +1,41 @@
+//! A module for describing the color capability of a terminal.
+//!
+//! This module provides an enum `ColorLevel` that represents how many colors
+//! a terminal is able to show. `StyledText::paint` uses it to downsample
+//! `Rgb` and `PaletteColor` styles into colors the terminal can display.
+
+// =======================================================================
+
+/// An enum representing the color capability of a terminal.
+///
+/// The variants are ordered from the least to the most capable level,
+/// so they can be compared with `<` and `>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum ColorLevel {
+    /// No colors and no escape codes at all, only the plain text.
+    NoColor,
+    /// The 16 basic colors (`30..=37`, `90..=97` and their backgrounds).
+    Ansi16,
+    /// The 256 colors of the xterm palette (`38;5;n`).
+    Ansi256,
+    /// 24 bit colors (`38;2;r;g;b`).
+    #[default]
+    TrueColor,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_level() {
+        assert_eq!(ColorLevel::default(), ColorLevel::TrueColor);
+    }
+
+    #[test]
+    fn test_level_order() {
+        assert!(ColorLevel::NoColor < ColorLevel::Ansi16);
+        assert!(ColorLevel::Ansi16 < ColorLevel::Ansi256);
+        assert!(ColorLevel::Ansi256 < ColorLevel::TrueColor);
+    }
+}
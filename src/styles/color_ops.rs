@@ -0,0 +1,188 @@
+//! A module for deriving colors from other colors.
+//!
+//! This module adds methods to `Rgb` that return a new color: lighter, darker,
+//! more or less saturated, mixed with another color, inverted or gray.
+//! It also provides the WCAG relative luminance and contrast ratio,
+//! for example to pick black or white text for a background.
+
+// =======================================================================
+
+use super::{ color_space::srgb_to_linear, rgb::Rgb };
+
+// =======================================================================
+
+/// The black color, the darkest text `contrasting_text` returns.
+const BLACK: Rgb = Rgb { r: 0, g: 0, b: 0 };
+/// The white color, the lightest text `contrasting_text` returns.
+const WHITE: Rgb = Rgb { r: 255, g: 255, b: 255 };
+
+impl Rgb {
+    /// Returns the color with its HSL lightness raised by `amount`, from 0.0 to 1.0.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styles::rgb::Rgb;
+    /// let red = Rgb { r: 255, g: 0, b: 0 };
+    /// assert_eq!(red.lighten(0.2), Rgb { r: 255, g: 102, b: 102 });
+    /// ```
+    pub fn lighten(&self, amount: f64) -> Rgb {
+        let mut hsl = self.to_hsl();
+        hsl.l = (hsl.l + amount).clamp(0.0, 1.0);
+        hsl.into()
+    }
+
+    /// Returns the color with its HSL lightness lowered by `amount`, from 0.0 to 1.0.
+    pub fn darken(&self, amount: f64) -> Rgb {
+        self.lighten(-amount)
+    }
+
+    /// Returns the color with its HSL saturation raised by `amount`, from 0.0 to 1.0.
+    pub fn saturate(&self, amount: f64) -> Rgb {
+        let mut hsl = self.to_hsl();
+        hsl.s = (hsl.s + amount).clamp(0.0, 1.0);
+        hsl.into()
+    }
+
+    /// Returns the color with its HSL saturation lowered by `amount`, from 0.0 to 1.0.
+    pub fn desaturate(&self, amount: f64) -> Rgb {
+        self.saturate(-amount)
+    }
+
+    /// Returns a mix of the two colors, `t` is how much of `other` is in the mix.
+    ///
+    /// `0.0` returns this color and `1.0` returns `other`, `t` is clamped to that range.
+    /// Each channel is mixed on its own, like CSS and Sass mix colors.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styles::rgb::Rgb;
+    /// let black = Rgb { r: 0, g: 0, b: 0 };
+    /// let white = Rgb { r: 255, g: 255, b: 255 };
+    /// assert_eq!(black.mix(&white, 0.5), Rgb { r: 128, g: 128, b: 128 });
+    /// ```
+    pub fn mix(&self, other: &Rgb, t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Rgb {
+            r: channel(self.r, other.r),
+            g: channel(self.g, other.g),
+            b: channel(self.b, other.b),
+        }
+    }
+
+    /// Returns the inverted color, each channel is subtracted from 255.
+    pub fn invert(&self) -> Rgb {
+        Rgb { r: 255 - self.r, g: 255 - self.g, b: 255 - self.b }
+    }
+
+    /// Returns the gray with the same perceived lightness as the color.
+    ///
+    /// The lightness is kept in the OKLab color space, so a pure blue becomes
+    /// a darker gray than a pure yellow.
+    pub fn grayscale(&self) -> Rgb {
+        Rgb::from_oklab(self.to_oklab().l, 0.0, 0.0)
+    }
+
+    /// Returns the relative luminance of the color as WCAG 2 defines it,
+    /// from 0.0 for black to 1.0 for white.
+    pub fn relative_luminance(&self) -> f64 {
+        0.2126 * srgb_to_linear(self.r) + 0.7152 * srgb_to_linear(self.g) + 0.0722 * srgb_to_linear(self.b)
+    }
+
+    /// Returns the WCAG 2 contrast ratio of the two colors, from 1.0 up to 21.0.
+    ///
+    /// WCAG asks for at least 4.5 for normal text and 3.0 for large text.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styles::rgb::Rgb;
+    /// let black = Rgb { r: 0, g: 0, b: 0 };
+    /// let white = Rgb { r: 255, g: 255, b: 255 };
+    /// assert_eq!(black.contrast_ratio(&white), 21.0);
+    /// ```
+    pub fn contrast_ratio(&self, other: &Rgb) -> f64 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Returns black or white, whichever has the higher contrast on this color.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styled;
+    /// use term_tools::styles::rgb::Rgb;
+    /// let background = Rgb { r: 30, g: 144, b: 255 };
+    /// let text = background.contrasting_text();
+    /// let label = styled(" OK ")
+    ///     .rgb(background.r, background.g, background.b)
+    ///     .bg()
+    ///     .rgb(text.r, text.g, text.b)
+    ///     .fg()
+    ///     .paint();
+    /// ```
+    pub fn contrasting_text(&self) -> Rgb {
+        if self.contrast_ratio(&BLACK) >= self.contrast_ratio(&WHITE) {
+            BLACK
+        } else {
+            WHITE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DODGER_BLUE: Rgb = Rgb { r: 30, g: 144, b: 255 };
+
+    #[test]
+    fn test_lighten_and_darken() {
+        assert_eq!(DODGER_BLUE.lighten(0.1), Rgb { r: 81, g: 169, b: 255 });
+        assert_eq!(DODGER_BLUE.darken(0.1), Rgb { r: 0, g: 119, b: 234 });
+        assert_eq!(DODGER_BLUE.lighten(1.0), WHITE);
+        assert_eq!(DODGER_BLUE.darken(1.0), BLACK);
+    }
+
+    #[test]
+    fn test_saturate_and_desaturate() {
+        let muted = Rgb { r: 100, g: 120, b: 140 };
+        assert_eq!(muted.desaturate(1.0), Rgb { r: 120, g: 120, b: 120 });
+        assert_eq!(muted.saturate(0.1), Rgb { r: 88, g: 120, b: 152 });
+        assert_eq!(DODGER_BLUE.saturate(0.5), DODGER_BLUE);
+    }
+
+    #[test]
+    fn test_mix() {
+        let red = Rgb { r: 255, g: 0, b: 0 };
+        assert_eq!(red.mix(&DODGER_BLUE, 0.0), red);
+        assert_eq!(red.mix(&DODGER_BLUE, 1.0), DODGER_BLUE);
+        assert_eq!(red.mix(&DODGER_BLUE, 2.0), DODGER_BLUE);
+        assert_eq!(red.mix(&DODGER_BLUE, 0.25), Rgb { r: 199, g: 36, b: 64 });
+    }
+
+    #[test]
+    fn test_invert_and_grayscale() {
+        assert_eq!(DODGER_BLUE.invert(), Rgb { r: 225, g: 111, b: 0 });
+        let gray = DODGER_BLUE.grayscale();
+        assert_eq!(gray.r, gray.g);
+        assert_eq!(gray.g, gray.b);
+        assert!(Rgb { r: 0, g: 0, b: 255 }.grayscale().r < Rgb { r: 255, g: 255, b: 0 }.grayscale().r);
+    }
+
+    #[test]
+    fn test_luminance_and_contrast() {
+        assert_eq!(BLACK.relative_luminance(), 0.0);
+        assert_eq!(WHITE.relative_luminance(), 1.0);
+        assert_eq!(WHITE.contrast_ratio(&WHITE), 1.0);
+        let ratio = DODGER_BLUE.contrast_ratio(&WHITE);
+        assert!((ratio - 3.24).abs() < 0.01, "{}", ratio);
+        assert_eq!(DODGER_BLUE.contrast_ratio(&WHITE), WHITE.contrast_ratio(&DODGER_BLUE));
+    }
+
+    #[test]
+    fn test_contrasting_text() {
+        assert_eq!(DODGER_BLUE.contrasting_text(), BLACK);
+        assert_eq!(Rgb { r: 0, g: 0, b: 128 }.contrasting_text(), WHITE);
+        assert_eq!(Rgb { r: 255, g: 255, b: 0 }.contrasting_text(), BLACK);
+    }
+}
@@ -0,0 +1,94 @@
+//! A module for the colors a terminal shows.
+//!
+//! This module provides a struct `ColorScheme` that holds the rgb values of the 16 basic colors
+//! and the default foreground and background color, like the color settings of a terminal.
+//! Renderers that do not write escape codes, like the HTML renderer, use it to turn basic and
+//! palette colors into rgb colors.
+
+// =======================================================================
+
+use super::{ basic_color::BASIC_COLORS, color::Color, palette::PaletteColor, rgb::Rgb };
+
+// =======================================================================
+
+/// A struct representing the colors of a terminal.
+///
+/// The palette colors 0 to 15 are the basic colors of the scheme,
+/// the other palette colors are the same in every scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColorScheme {
+    /// The color of text without a foreground color.
+    pub foreground: Rgb,
+    /// The color behind text without a background color.
+    pub background: Rgb,
+    /// The 16 basic colors in the order of their palette index.
+    pub basic: [Rgb; 16],
+}
+
+impl ColorScheme {
+    /// Returns the colors of xterm, with light gray text on black.
+    pub fn xterm() -> Self {
+        ColorScheme {
+            foreground: Rgb { r: 229, g: 229, b: 229 },
+            background: Rgb { r: 0, g: 0, b: 0 },
+            basic: BASIC_COLORS.map(|(_, rgb)| rgb),
+        }
+    }
+
+    /// Returns the colors of xterm with black text on white, for pages with a light background.
+    pub fn xterm_light() -> Self {
+        ColorScheme {
+            foreground: Rgb { r: 0, g: 0, b: 0 },
+            background: Rgb { r: 255, g: 255, b: 255 },
+            ..ColorScheme::xterm()
+        }
+    }
+
+    /// Returns the rgb value of a color in this scheme.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styles::{ color::Color, color_scheme::ColorScheme, palette::PaletteColor, rgb::Rgb };
+    /// let mut scheme = ColorScheme::xterm();
+    /// scheme.basic[1] = Rgb { r: 220, g: 50, b: 47 };
+    /// assert_eq!(scheme.resolve(&Color::Palette(PaletteColor { index: 1 })), Rgb { r: 220, g: 50, b: 47 });
+    /// assert_eq!(scheme.resolve(&Color::Palette(PaletteColor { index: 196 })), Rgb { r: 255, g: 0, b: 0 });
+    /// ```
+    pub fn resolve(&self, color: &Color) -> Rgb {
+        match color {
+            Color::Basic(c) => c.index().map_or(self.foreground, |i| self.basic[i as usize]),
+            Color::Palette(PaletteColor { index }) if *index < 16 => self.basic[*index as usize],
+            Color::Palette(c) => c.to_rgb(),
+            Color::Rgb(c) => *c,
+        }
+    }
+}
+
+impl Default for ColorScheme {
+    /// Returns the xterm scheme.
+    fn default() -> Self {
+        ColorScheme::xterm()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styles::basic_color::BasicColor;
+
+    #[test]
+    fn resolve_colors() {
+        let scheme = ColorScheme::xterm();
+        assert_eq!(scheme.resolve(&Color::Basic(BasicColor { fg: 31, bg: 41 })), Rgb { r: 205, g: 0, b: 0 });
+        assert_eq!(scheme.resolve(&Color::Palette(PaletteColor { index: 232 })), Rgb { r: 8, g: 8, b: 8 });
+        assert_eq!(scheme.resolve(&Color::Rgb(Rgb { r: 1, g: 2, b: 3 })), Rgb { r: 1, g: 2, b: 3 });
+    }
+
+    #[test]
+    fn custom_basic_colors() {
+        let mut scheme = ColorScheme::xterm_light();
+        scheme.basic[4] = Rgb { r: 38, g: 139, b: 210 };
+        assert_eq!(scheme.resolve(&Color::Basic(BasicColor { fg: 34, bg: 44 })), Rgb { r: 38, g: 139, b: 210 });
+        assert_eq!(scheme.background, Rgb { r: 255, g: 255, b: 255 });
+    }
+}
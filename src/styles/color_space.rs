@@ -0,0 +1,267 @@
+//! A module for converting colors between color spaces.
+//!
+//! This module provides the structs `Hsl`, `Hsv` and `Oklab` and the conversions
+//! between them and `Rgb`. HSL and HSV are handy for picking colors by hue,
+//! OKLab is a perceptual color space where equal distances look like equal differences.
+
+// =======================================================================
+
+use super::rgb::Rgb;
+
+// =======================================================================
+
+/// A struct representing a color by its hue, saturation and lightness.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    /// The hue in degrees, from 0.0 up to 360.0.
+    pub h: f64,
+    /// The saturation, from 0.0 to 1.0.
+    pub s: f64,
+    /// The lightness, from 0.0 to 1.0.
+    pub l: f64,
+}
+
+/// A struct representing a color by its hue, saturation and value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    /// The hue in degrees, from 0.0 up to 360.0.
+    pub h: f64,
+    /// The saturation, from 0.0 to 1.0.
+    pub s: f64,
+    /// The value, from 0.0 to 1.0.
+    pub v: f64,
+}
+
+/// A struct representing a color in the OKLab color space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklab {
+    /// The perceived lightness, from 0.0 for black to 1.0 for white.
+    pub l: f64,
+    /// How green (negative) or red (positive) the color is.
+    pub a: f64,
+    /// How blue (negative) or yellow (positive) the color is.
+    pub b: f64,
+}
+
+impl Rgb {
+    /// Returns the rgb color of a hue in degrees, a saturation and a lightness.
+    ///
+    /// The hue wraps around, so `-120.0` is the same as `240.0`.
+    /// The saturation and the lightness are clamped to `0.0..=1.0`.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styles::rgb::Rgb;
+    /// assert_eq!(Rgb::from_hsl(0.0, 1.0, 0.5), Rgb { r: 255, g: 0, b: 0 });
+    /// ```
+    pub fn from_hsl(hue: f64, saturation: f64, lightness: f64) -> Rgb {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        from_hue_and_chroma(hue, chroma, lightness - chroma / 2.0)
+    }
+
+    /// Returns the color as hue, saturation and lightness.
+    ///
+    /// Grays have a hue and a saturation of `0.0`.
+    pub fn to_hsl(&self) -> Hsl {
+        let (h, max, min) = hue_max_min(self);
+        let l = (max + min) / 2.0;
+        let s = if max == min { 0.0 } else { (max - min) / (1.0 - (2.0 * l - 1.0).abs()) };
+        Hsl { h, s: s.clamp(0.0, 1.0), l }
+    }
+
+    /// Returns the rgb color of a hue in degrees, a saturation and a value.
+    ///
+    /// The hue wraps around, the saturation and the value are clamped to `0.0..=1.0`.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styles::rgb::Rgb;
+    /// assert_eq!(Rgb::from_hsv(240.0, 1.0, 0.5), Rgb { r: 0, g: 0, b: 128 });
+    /// ```
+    pub fn from_hsv(hue: f64, saturation: f64, value: f64) -> Rgb {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let value = value.clamp(0.0, 1.0);
+        let chroma = value * saturation;
+        from_hue_and_chroma(hue, chroma, value - chroma)
+    }
+
+    /// Returns the color as hue, saturation and value.
+    ///
+    /// Grays have a hue and a saturation of `0.0`.
+    pub fn to_hsv(&self) -> Hsv {
+        let (h, max, min) = hue_max_min(self);
+        let s = if max == 0.0 { 0.0 } else { (max - min) / max };
+        Hsv { h, s, v: max }
+    }
+
+    /// Returns the rgb color of an OKLab color, colors outside of sRGB are clamped.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styles::rgb::Rgb;
+    /// assert_eq!(Rgb::from_oklab(1.0, 0.0, 0.0), Rgb { r: 255, g: 255, b: 255 });
+    /// ```
+    pub fn from_oklab(l: f64, a: f64, b: f64) -> Rgb {
+        let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+        let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+        let s_ = (l - 0.0894841775 * a - 1.291485548 * b).powi(3);
+        Rgb {
+            r: linear_to_srgb(4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_),
+            g: linear_to_srgb(-1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_),
+            b: linear_to_srgb(-0.0041960863 * l_ - 0.7034186147 * m_ + 1.707614701 * s_),
+        }
+    }
+
+    /// Returns the color in the OKLab color space.
+    pub fn to_oklab(&self) -> Oklab {
+        let (r, g, b) = (srgb_to_linear(self.r), srgb_to_linear(self.g), srgb_to_linear(self.b));
+        let l_ = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m_ = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s_ = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+        Oklab {
+            l: 0.2104542553 * l_ + 0.793617785 * m_ - 0.0040720468 * s_,
+            a: 1.9779984951 * l_ - 2.428592205 * m_ + 0.4505937099 * s_,
+            b: 0.0259040371 * l_ + 0.7827717662 * m_ - 0.808675766 * s_,
+        }
+    }
+}
+
+impl From<Hsl> for Rgb {
+    fn from(hsl: Hsl) -> Self {
+        Rgb::from_hsl(hsl.h, hsl.s, hsl.l)
+    }
+}
+
+impl From<Hsv> for Rgb {
+    fn from(hsv: Hsv) -> Self {
+        Rgb::from_hsv(hsv.h, hsv.s, hsv.v)
+    }
+}
+
+impl From<Oklab> for Rgb {
+    fn from(lab: Oklab) -> Self {
+        Rgb::from_oklab(lab.l, lab.a, lab.b)
+    }
+}
+
+/// Returns the rgb color of a hue, a chroma and the amount `m` that is added to every channel.
+fn from_hue_and_chroma(hue: f64, chroma: f64, m: f64) -> Rgb {
+    let hue = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - ((hue % 2.0) - 1.0).abs());
+    let (r, g, b) = match hue as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let channel = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    Rgb { r: channel(r), g: channel(g), b: channel(b) }
+}
+
+/// Returns the hue in degrees and the largest and the smallest channel from 0.0 to 1.0.
+fn hue_max_min(rgb: &Rgb) -> (f64, f64, f64) {
+    let (r, g, b) = (rgb.r as f64 / 255.0, rgb.g as f64 / 255.0, rgb.b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    (hue, max, min)
+}
+
+/// Converts an sRGB channel into linear light from 0.0 to 1.0.
+pub(crate) fn srgb_to_linear(channel: u8) -> f64 {
+    let c = channel as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts linear light into an sRGB channel, values outside of `0.0..=1.0` are clamped.
+pub(crate) fn linear_to_srgb(linear: f64) -> u8 {
+    let c = if linear <= 0.0031308 {
+        12.92 * linear
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DODGER_BLUE: Rgb = Rgb { r: 30, g: 144, b: 255 };
+
+    fn assert_close(left: f64, right: f64) {
+        assert!((left - right).abs() < 0.001, "{} is not close to {}", left, right);
+    }
+
+    #[test]
+    fn test_from_hsl() {
+        assert_eq!(Rgb::from_hsl(120.0, 1.0, 0.5), Rgb { r: 0, g: 255, b: 0 });
+        assert_eq!(Rgb::from_hsl(600.0, 1.0, 0.5), Rgb { r: 0, g: 0, b: 255 });
+        assert_eq!(Rgb::from_hsl(0.0, 0.0, 0.5), Rgb { r: 128, g: 128, b: 128 });
+        assert_eq!(Rgb::from_hsl(300.0, 2.0, 1.5), Rgb { r: 255, g: 255, b: 255 });
+    }
+
+    #[test]
+    fn test_to_hsl() {
+        let hsl = DODGER_BLUE.to_hsl();
+        assert_close(hsl.h, 209.6);
+        assert_close(hsl.s, 1.0);
+        assert_close(hsl.l, 0.5588);
+        assert_eq!(Rgb::from(hsl), DODGER_BLUE);
+        assert_eq!(Rgb { r: 50, g: 50, b: 50 }.to_hsl().s, 0.0);
+    }
+
+    #[test]
+    fn test_hsv() {
+        let hsv = DODGER_BLUE.to_hsv();
+        assert_close(hsv.h, 209.6);
+        assert_close(hsv.s, 0.8824);
+        assert_close(hsv.v, 1.0);
+        assert_eq!(Rgb::from(hsv), DODGER_BLUE);
+        assert_eq!(Rgb::from_hsv(0.0, 0.0, 0.0), Rgb { r: 0, g: 0, b: 0 });
+    }
+
+    #[test]
+    fn test_oklab() {
+        let lab = Rgb { r: 255, g: 0, b: 0 }.to_oklab();
+        assert_close(lab.l, 0.628);
+        assert_close(lab.a, 0.2249);
+        assert_close(lab.b, 0.1258);
+        let white = Rgb { r: 255, g: 255, b: 255 }.to_oklab();
+        assert_close(white.l, 1.0);
+        assert_close(white.a, 0.0);
+        assert_eq!(Rgb::from(DODGER_BLUE.to_oklab()), DODGER_BLUE);
+        assert_eq!(Rgb::from_oklab(2.0, 0.0, 0.0), Rgb { r: 255, g: 255, b: 255 });
+    }
+
+    #[test]
+    fn test_round_trips() {
+        for r in (0..=255).step_by(51) {
+            for g in (0..=255).step_by(51) {
+                for b in (0..=255).step_by(51) {
+                    let rgb = Rgb { r, g, b };
+                    assert_eq!(Rgb::from(rgb.to_hsl()), rgb);
+                    assert_eq!(Rgb::from(rgb.to_hsv()), rgb);
+                    assert_eq!(Rgb::from(rgb.to_oklab()), rgb);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! A module for reading colors from text.
+//!
+//! This module implements `FromStr` and `TryFrom<&str>` for `Rgb`, so colors can be read
+//! from config files as hex codes (`#1e90ff` or `#fff`), `rgb(30, 144, 255)`,
+//! `hsl(210, 100%, 56%)` or CSS names like `dodgerblue`.
+//! When a spec is rejected, the `ParseColorError` says why.
+//! `Rgb::to_hex` writes a color back as a hex code.
+
+// =======================================================================
+
+use std::{ error::Error, fmt, str::FromStr };
+
+use super::{ css_color::css_color, rgb::Rgb };
+
+// =======================================================================
+
+/// An enum representing why a color spec could not be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseColorError {
+    /// The spec is empty or only whitespace.
+    Empty,
+    /// A hex code without 3 or 6 digits, with the number of digits it has.
+    InvalidHexLength(usize),
+    /// A hex code with a character that is not a hex digit.
+    InvalidHexDigit(char),
+    /// A function that is not one of `rgb` and `hsl`.
+    UnknownFunction(String),
+    /// A function without its closing parenthesis.
+    UnclosedFunction(String),
+    /// A function with the wrong number of arguments.
+    WrongArgumentCount { expected: usize, found: usize },
+    /// An argument that is not a number.
+    InvalidNumber(String),
+    /// An argument that is out of its range, like `300` for a red value.
+    OutOfRange(String),
+    /// A name that is not one of the CSS named colors.
+    UnknownName(String),
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseColorError::Empty => f.write_str("the color is empty"),
+            ParseColorError::InvalidHexLength(len) => {
+                write!(f, "a hex color needs 3 or 6 digits, found {}", len)
+            }
+            ParseColorError::InvalidHexDigit(c) => write!(f, "`{}` is not a hex digit", c),
+            ParseColorError::UnknownFunction(name) => {
+                write!(f, "`{}` is not a color function, expected `rgb` or `hsl`", name)
+            }
+            ParseColorError::UnclosedFunction(spec) => write!(f, "`{}` is missing a closing `)`", spec),
+            ParseColorError::WrongArgumentCount { expected, found } => {
+                write!(f, "expected {} arguments, found {}", expected, found)
+            }
+            ParseColorError::InvalidNumber(arg) => write!(f, "`{}` is not a number", arg),
+            ParseColorError::OutOfRange(arg) => write!(f, "`{}` is out of range", arg),
+            ParseColorError::UnknownName(name) => write!(f, "`{}` is not a known color name", name),
+        }
+    }
+}
+
+impl Error for ParseColorError {}
+
+impl FromStr for Rgb {
+    type Err = ParseColorError;
+
+    /// Reads a color from a hex code, an `rgb()` or `hsl()` function or a CSS name.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styles::rgb::Rgb;
+    /// let dodger_blue = Rgb { r: 30, g: 144, b: 255 };
+    /// assert_eq!("#1e90ff".parse::<Rgb>(), Ok(dodger_blue));
+    /// assert_eq!("rgb(30, 144, 255)".parse::<Rgb>(), Ok(dodger_blue));
+    /// assert_eq!("dodgerblue".parse::<Rgb>(), Ok(dodger_blue));
+    /// assert_eq!("hsl(0, 100%, 50%)".parse::<Rgb>(), Ok(Rgb { r: 255, g: 0, b: 0 }));
+    /// ```
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let spec = spec.trim();
+        if spec.is_empty() {
+            return Err(ParseColorError::Empty);
+        }
+        if let Some(hex) = spec.strip_prefix('#') {
+            return parse_hex(hex);
+        }
+        if let Some(open) = spec.find('(') {
+            let name = spec[..open].trim().to_ascii_lowercase();
+            let args = spec[open + 1..]
+                .strip_suffix(')')
+                .ok_or_else(|| ParseColorError::UnclosedFunction(spec.to_string()))?;
+            let args = split_arguments(args);
+            return match name.as_str() {
+                "rgb" => parse_rgb(&args),
+                "hsl" => parse_hsl(&args),
+                _ => Err(ParseColorError::UnknownFunction(name)),
+            };
+        }
+        css_color(spec).ok_or_else(|| ParseColorError::UnknownName(spec.to_string()))
+    }
+}
+
+impl TryFrom<&str> for Rgb {
+    type Error = ParseColorError;
+
+    /// Reads a color like `str::parse` does, see the `FromStr` implementation of `Rgb`.
+    fn try_from(spec: &str) -> Result<Self, Self::Error> {
+        spec.parse()
+    }
+}
+
+impl Rgb {
+    /// Returns the color as a lowercase hex code like `#1e90ff`.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::styles::rgb::Rgb;
+    /// assert_eq!(Rgb { r: 30, g: 144, b: 255 }.to_hex(), "#1e90ff");
+    /// ```
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// Reads the digits of a hex code without its `#`, in the `rgb` or `rrggbb` form.
+pub(crate) fn parse_hex(hex: &str) -> Result<Rgb, ParseColorError> {
+    if let Some(c) = hex.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(ParseColorError::InvalidHexDigit(c));
+    }
+    let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).unwrap_or(0);
+    match hex.len() {
+        3 => Ok(Rgb { r: digit(0) * 17, g: digit(1) * 17, b: digit(2) * 17 }),
+        6 => {
+            Ok(Rgb {
+                r: digit(0) * 16 + digit(1),
+                g: digit(2) * 16 + digit(3),
+                b: digit(4) * 16 + digit(5),
+            })
+        }
+        len => Err(ParseColorError::InvalidHexLength(len)),
+    }
+}
+
+/// Splits the arguments of a function by commas, or by whitespace if there is no comma.
+fn split_arguments(args: &str) -> Vec<&str> {
+    if args.contains(',') {
+        args.split(',').map(str::trim).collect()
+    } else {
+        args.split_whitespace().collect()
+    }
+}
+
+/// Checks that a function got the expected number of arguments.
+fn expect_arguments(args: &[&str], expected: usize) -> Result<(), ParseColorError> {
+    if args.len() != expected {
+        return Err(ParseColorError::WrongArgumentCount { expected, found: args.len() });
+    }
+    Ok(())
+}
+
+/// Reads a number and checks that it is in `0..=max`.
+fn parse_number(arg: &str, number: &str, max: f64) -> Result<f64, ParseColorError> {
+    let value: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| ParseColorError::InvalidNumber(arg.to_string()))?;
+    if !(0.0..=max).contains(&value) {
+        return Err(ParseColorError::OutOfRange(arg.to_string()));
+    }
+    Ok(value)
+}
+
+/// Reads the arguments of `rgb()`, each a number from 0 to 255 or a percentage.
+fn parse_rgb(args: &[&str]) -> Result<Rgb, ParseColorError> {
+    expect_arguments(args, 3)?;
+    let channel = |arg: &str| -> Result<u8, ParseColorError> {
+        let value = match arg.strip_suffix('%') {
+            Some(percent) => parse_number(arg, percent, 100.0)? / 100.0 * 255.0,
+            None => parse_number(arg, arg, 255.0)?,
+        };
+        Ok(value.round() as u8)
+    };
+    Ok(Rgb { r: channel(args[0])?, g: channel(args[1])?, b: channel(args[2])? })
+}
+
+/// Reads the arguments of `hsl()`: the hue in degrees, then the saturation and the lightness
+/// as percentages. The `%` signs and a `deg` after the hue are optional.
+fn parse_hsl(args: &[&str]) -> Result<Rgb, ParseColorError> {
+    expect_arguments(args, 3)?;
+    let hue = args[0].strip_suffix("deg").unwrap_or(args[0]);
+    let hue: f64 = hue
+        .trim()
+        .parse()
+        .map_err(|_| ParseColorError::InvalidNumber(args[0].to_string()))?;
+    if !hue.is_finite() {
+        return Err(ParseColorError::OutOfRange(args[0].to_string()));
+    }
+    let percent = |arg: &str| parse_number(arg, arg.strip_suffix('%').unwrap_or(arg), 100.0);
+    let saturation = percent(args[1])?;
+    let lightness = percent(args[2])?;
+    Ok(Rgb::from_hsl(hue, saturation / 100.0, lightness / 100.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DODGER_BLUE: Rgb = Rgb { r: 30, g: 144, b: 255 };
+
+    #[test]
+    fn parse_hex_codes() {
+        assert_eq!("#1e90ff".parse(), Ok(DODGER_BLUE));
+        assert_eq!("#1E90FF".parse(), Ok(DODGER_BLUE));
+        assert_eq!(" #fff ".parse(), Ok(Rgb { r: 255, g: 255, b: 255 }));
+        assert_eq!("#1e90f".parse::<Rgb>(), Err(ParseColorError::InvalidHexLength(5)));
+        assert_eq!("#".parse::<Rgb>(), Err(ParseColorError::InvalidHexLength(0)));
+        assert_eq!("#1e90fg".parse::<Rgb>(), Err(ParseColorError::InvalidHexDigit('g')));
+    }
+
+    #[test]
+    fn parse_rgb_functions() {
+        assert_eq!("rgb(30,144,255)".parse(), Ok(DODGER_BLUE));
+        assert_eq!("RGB( 30 , 144 , 255 )".parse(), Ok(DODGER_BLUE));
+        assert_eq!("rgb(30 144 255)".parse(), Ok(DODGER_BLUE));
+        assert_eq!("rgb(100%, 50%, 0%)".parse(), Ok(Rgb { r: 255, g: 128, b: 0 }));
+        assert_eq!(
+            "rgb(30, 144)".parse::<Rgb>(),
+            Err(ParseColorError::WrongArgumentCount { expected: 3, found: 2 })
+        );
+        assert_eq!("rgb(30, 144, 256)".parse::<Rgb>(), Err(ParseColorError::OutOfRange("256".to_string())));
+        assert_eq!("rgb(30, x, 255)".parse::<Rgb>(), Err(ParseColorError::InvalidNumber("x".to_string())));
+        assert_eq!(
+            "rgb(30, 144, 255".parse::<Rgb>(),
+            Err(ParseColorError::UnclosedFunction("rgb(30, 144, 255".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_hsl_functions() {
+        assert_eq!("hsl(210, 100%, 56%)".parse(), Ok(Rgb { r: 31, g: 143, b: 255 }));
+        assert_eq!("hsl(120deg 100% 25%)".parse(), Ok(Rgb { r: 0, g: 128, b: 0 }));
+        assert_eq!("hsl(-120, 100%, 50%)".parse(), Ok(Rgb { r: 0, g: 0, b: 255 }));
+        assert_eq!("hsl(0, 0%, 100%)".parse(), Ok(Rgb { r: 255, g: 255, b: 255 }));
+        assert_eq!("hsl(0, 120%, 50%)".parse::<Rgb>(), Err(ParseColorError::OutOfRange("120%".to_string())));
+        assert_eq!("hwb(0, 0%, 0%)".parse::<Rgb>(), Err(ParseColorError::UnknownFunction("hwb".to_string())));
+    }
+
+    #[test]
+    fn parse_names() {
+        assert_eq!("dodgerblue".parse(), Ok(DODGER_BLUE));
+        assert_eq!(Rgb::try_from("DodgerBlue"), Ok(DODGER_BLUE));
+        assert_eq!("blurple".parse::<Rgb>(), Err(ParseColorError::UnknownName("blurple".to_string())));
+        assert_eq!("  ".parse::<Rgb>(), Err(ParseColorError::Empty));
+    }
+
+    #[test]
+    fn error_messages() {
+        assert_eq!(ParseColorError::InvalidHexLength(5).to_string(), "a hex color needs 3 or 6 digits, found 5");
+        assert_eq!(
+            ParseColorError::UnknownName("blurple".to_string()).to_string(),
+            "`blurple` is not a known color name"
+        );
+    }
+}
@@ -1,13 +1,59 @@
-use super::{ paint_type::PaintType, Styles, Stylify };
+use std::fmt;
+
+use super::{ paint_type::PaintType, Styles, Stylify, NAMES };
 
 // =======================================================================
 
 /// A struct representing a Formatter with code value.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Formatter {
     pub code: u8,
 }
 
+impl Formatter {
+    /// Returns the code that turns the formatter off.
+    ///
+    /// For example `22` for bold and faint, `23` for italic and `24` for underline.
+    /// If the formatter is `RESET`, an off code itself or unknown, `None` is returned.
+    pub const fn off_code(&self) -> Option<u8> {
+        match self.code {
+            1 | 2 => Some(22),
+            3 => Some(23),
+            4 | 21 => Some(24),
+            5 | 6 => Some(25),
+            7 => Some(27),
+            8 => Some(28),
+            9 => Some(29),
+            26 => Some(50),
+            51 | 52 => Some(54),
+            53 => Some(55),
+            73 | 74 => Some(75),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the formatter turns other formatters off, like `22` or `24`.
+    pub const fn is_off_code(&self) -> bool {
+        matches!(self.code, 22..=25 | 27..=29 | 50 | 54 | 55 | 75)
+    }
+
+    /// Returns the name of the formatter like the constant of its code in lowercase, like `bold`.
+    ///
+    /// Off codes have their names too, like `no_italic`, unknown codes have no name.
+    pub const fn name(&self) -> Option<&'static str> {
+        let mut i = 0;
+        while i < NAMES.len() {
+            if let Styles::StyleFormatter(f) = NAMES[i].1 {
+                if f.code == self.code {
+                    return Some(NAMES[i].0);
+                }
+            }
+            i += 1;
+        }
+        None
+    }
+}
+
 impl Stylify for Formatter {
     /// Returns a string representation of the formatter style.
     ///
@@ -15,6 +61,11 @@ impl Stylify for Formatter {
     fn make_styles(&self, _paint_type: Option<&PaintType>) -> String {
         format!("{}", self.code)
     }
+
+    /// Writes the formatter code into `f` without allocating.
+    fn write_styles<W: fmt::Write>(&self, f: &mut W, _paint_type: Option<&PaintType>) -> fmt::Result {
+        write!(f, "{}", self.code)
+    }
 }
 
 /// A macro for generating formatter constants.
@@ -31,7 +82,99 @@ formatter_code!(ITALIC, 3);
 formatter_code!(UNDERLINE, 4);
 formatter_code!(SLOW_BLINK, 5);
 formatter_code!(RAPID_BLINK, 6);
+formatter_code!(INVERSE, 7);
+formatter_code!(CONCEAL, 8);
+formatter_code!(STRIKETHROUGH, 9);
+formatter_code!(DOUBLE_UNDERLINE, 21);
+formatter_code!(PROPORTIONAL_SPACING, 26);
+formatter_code!(FRAMED, 51);
+formatter_code!(ENCIRCLED, 52);
 formatter_code!(OVERLINE, 53);
+formatter_code!(SUPERSCRIPT, 73);
+formatter_code!(SUBSCRIPT, 74);
+
+// Off codes
+
+formatter_code!(NORMAL_INTENSITY, 22);
+formatter_code!(NO_ITALIC, 23);
+formatter_code!(NO_UNDERLINE, 24);
+formatter_code!(NO_BLINK, 25);
+formatter_code!(NO_INVERSE, 27);
+formatter_code!(REVEAL, 28);
+formatter_code!(NO_STRIKETHROUGH, 29);
+formatter_code!(NO_PROPORTIONAL_SPACING, 50);
+formatter_code!(NO_FRAMED, 54);
+formatter_code!(NO_OVERLINE, 55);
+formatter_code!(NO_SCRIPT, 75);
+
+/// A struct representing a set of active formatters.
+///
+/// Each formatter that can be turned on is stored as one bit of its code,
+/// so the set is cheap to copy and compare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FormatterSet {
+    bits: u128,
+}
+
+impl FormatterSet {
+    /// Returns an empty set.
+    pub const fn new() -> Self {
+        FormatterSet { bits: 0 }
+    }
+
+    /// Returns `true` if there is no formatter in the set.
+    pub const fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// Returns `true` if the formatter is in the set.
+    pub const fn contains(&self, formatter: &Formatter) -> bool {
+        formatter.code < 128 && self.bits & (1 << formatter.code) != 0
+    }
+
+    /// Inserts the formatter into the set, no matter what its code means.
+    ///
+    /// Codes from 128 on can not be stored and are ignored.
+    pub const fn insert(&mut self, formatter: &Formatter) {
+        if formatter.code < 128 {
+            self.bits |= 1 << formatter.code;
+        }
+    }
+
+    /// Removes the formatter from the set.
+    pub const fn remove(&mut self, formatter: &Formatter) {
+        if formatter.code < 128 {
+            self.bits &= !(1 << formatter.code);
+        }
+    }
+
+    /// Applies a formatter to the set like a terminal would.
+    ///
+    /// `RESET` clears the set, off codes like `22` remove the formatters they turn off
+    /// and the other formatters are inserted. Unknown codes are ignored.
+    pub const fn apply(&mut self, formatter: &Formatter) {
+        if formatter.code == 0 {
+            self.bits = 0;
+        } else if formatter.is_off_code() {
+            let mut code = 0;
+            while code < 128 {
+                if let Some(off_code) = (Formatter { code }).off_code() {
+                    if off_code == formatter.code {
+                        self.bits &= !(1 << code);
+                    }
+                }
+                code += 1;
+            }
+        } else if formatter.off_code().is_some() {
+            self.bits |= 1 << formatter.code;
+        }
+    }
+
+    /// Returns the formatters of the set ordered by their code.
+    pub fn iter(&self) -> impl Iterator<Item = Formatter> + '_ {
+        (0..128u8).filter(|c| self.bits & (1 << c) != 0).map(|code| Formatter { code })
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -87,6 +230,70 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_off_code() {
+        assert_eq!(Formatter { code: 1 }.off_code(), Some(22));
+        assert_eq!(Formatter { code: 2 }.off_code(), Some(22));
+        assert_eq!(Formatter { code: 3 }.off_code(), Some(23));
+        assert_eq!(Formatter { code: 4 }.off_code(), Some(24));
+        assert_eq!(Formatter { code: 6 }.off_code(), Some(25));
+        assert_eq!(Formatter { code: 53 }.off_code(), Some(55));
+        assert_eq!(Formatter { code: 0 }.off_code(), None);
+        assert_eq!(Formatter { code: 22 }.off_code(), None);
+    }
+
+    #[test]
+    fn test_off_code_of_new_formatters() {
+        assert_eq!(Formatter { code: 7 }.off_code(), Some(27));
+        assert_eq!(Formatter { code: 8 }.off_code(), Some(28));
+        assert_eq!(Formatter { code: 9 }.off_code(), Some(29));
+        assert_eq!(Formatter { code: 21 }.off_code(), Some(24));
+        assert_eq!(Formatter { code: 26 }.off_code(), Some(50));
+        assert_eq!(Formatter { code: 51 }.off_code(), Some(54));
+        assert_eq!(Formatter { code: 52 }.off_code(), Some(54));
+        assert_eq!(Formatter { code: 73 }.off_code(), Some(75));
+        assert_eq!(Formatter { code: 74 }.off_code(), Some(75));
+    }
+
+    #[test]
+    fn test_formatter_set_remove() {
+        let mut set = FormatterSet::new();
+        set.insert(&Formatter { code: 7 });
+        set.insert(&Formatter { code: 9 });
+        set.remove(&Formatter { code: 7 });
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![Formatter { code: 9 }]);
+    }
+
+    #[test]
+    fn test_formatter_set_apply() {
+        let mut set = FormatterSet::new();
+        set.apply(&Formatter { code: 1 });
+        set.apply(&Formatter { code: 2 });
+        set.apply(&Formatter { code: 4 });
+        set.apply(&Formatter { code: 15 });
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![
+            Formatter { code: 1 },
+            Formatter { code: 2 },
+            Formatter { code: 4 },
+        ]);
+
+        set.apply(&Formatter { code: 22 });
+        assert!(!set.contains(&Formatter { code: 1 }));
+        assert!(!set.contains(&Formatter { code: 2 }));
+        assert!(set.contains(&Formatter { code: 4 }));
+
+        set.apply(&Formatter { code: 0 });
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn formatter_names() {
+        assert_eq!(Formatter { code: 1 }.name(), Some("bold"));
+        assert_eq!(Formatter { code: 74 }.name(), Some("subscript"));
+        assert_eq!(Formatter { code: 22 }.name(), Some("normal_intensity"));
+        assert_eq!(Formatter { code: 60 }.name(), None);
+    }
+
     /// A macro for generating formatter tests.
     macro_rules! formatter_test {
         ($test_name:ident, $formatter_name:ident, $code:expr) => {
@@ -111,5 +318,25 @@ mod tests {
     formatter_test!(test_underline_formatter, UNDERLINE, 4);
     formatter_test!(test_slow_blink_formatter, SLOW_BLINK, 5);
     formatter_test!(test_rapid_blink_formatter, RAPID_BLINK, 6);
+    formatter_test!(test_inverse_formatter, INVERSE, 7);
+    formatter_test!(test_conceal_formatter, CONCEAL, 8);
+    formatter_test!(test_strikethrough_formatter, STRIKETHROUGH, 9);
+    formatter_test!(test_double_underline_formatter, DOUBLE_UNDERLINE, 21);
+    formatter_test!(test_proportional_spacing_formatter, PROPORTIONAL_SPACING, 26);
+    formatter_test!(test_framed_formatter, FRAMED, 51);
+    formatter_test!(test_encircled_formatter, ENCIRCLED, 52);
     formatter_test!(test_overline_formatter, OVERLINE, 53);
+    formatter_test!(test_superscript_formatter, SUPERSCRIPT, 73);
+    formatter_test!(test_subscript_formatter, SUBSCRIPT, 74);
+    formatter_test!(test_normal_intensity_formatter, NORMAL_INTENSITY, 22);
+    formatter_test!(test_no_italic_formatter, NO_ITALIC, 23);
+    formatter_test!(test_no_underline_formatter, NO_UNDERLINE, 24);
+    formatter_test!(test_no_blink_formatter, NO_BLINK, 25);
+    formatter_test!(test_no_inverse_formatter, NO_INVERSE, 27);
+    formatter_test!(test_reveal_formatter, REVEAL, 28);
+    formatter_test!(test_no_strikethrough_formatter, NO_STRIKETHROUGH, 29);
+    formatter_test!(test_no_proportional_spacing_formatter, NO_PROPORTIONAL_SPACING, 50);
+    formatter_test!(test_no_framed_formatter, NO_FRAMED, 54);
+    formatter_test!(test_no_overline_formatter, NO_OVERLINE, 55);
+    formatter_test!(test_no_script_formatter, NO_SCRIPT, 75);
 }
@@ -1,23 +1,28 @@
-/// A module for working with paint types.
-///
-/// This module provides an enum `PaintType` that represents different types of paint.
-/// It also implements the `Stylify` trait for `PaintType`, which allows for generating styles based on the paint type.
+//! A module for working with paint types.
+//!
+//! This module provides an enum `PaintType` that represents different types of paint.
+//! It also implements the `Stylify` trait for `PaintType`, which allows for generating styles based on the paint type.
 
 // =======================================================================
 
+use std::fmt;
+
 use super::Stylify;
 
 // =======================================================================
 
 /// An enum representing different types of paint.
 ///
-/// This enum has two variants: `FG` and `BG`, which represent foreground and background paint, respectively.
-#[derive(Debug, Clone)]
+/// This enum has three variants: `FG`, `BG` and `UL`, which represent foreground, background
+/// and underline color paint, respectively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PaintType {
     /// Foreground paint.
     FG,
     /// Background paint.
     BG,
+    /// Underline color paint, written with the `58` parameter.
+    UL,
 }
 
 impl Stylify for PaintType {
@@ -28,6 +33,11 @@ impl Stylify for PaintType {
     fn make_styles(&self, _paint_type: Option<&PaintType>) -> String {
         String::new()
     }
+
+    /// Writes nothing, like `make_styles` returns an empty string.
+    fn write_styles<W: fmt::Write>(&self, _f: &mut W, _paint_type: Option<&PaintType>) -> fmt::Result {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -1,20 +1,25 @@
-/// A module for creating palette color.
-///
-/// This module provides an struct `PaletteColor`
-/// that represents palette color, index should be between 0 to 255 mean u8.
-/// It also implements the `Stylify` trait for `PaletteColor`,
-/// which allows for generating styles based on the paint type.
+//! A module for creating palette color.
+//!
+//! This module provides an struct `PaletteColor`
+//! that represents palette color, index should be between 0 to 255 mean u8.
+//! It also implements the `Stylify` trait for `PaletteColor`,
+//! which allows for generating styles based on the paint type.
 
 // =======================================================================
 
-use super::{ paint_type::PaintType, Stylify };
+use std::fmt;
+
+use super::{ basic_color::BasicColor, paint_type::PaintType, rgb::Rgb, Stylify };
 
 // =======================================================================
 
+/// The channel values of the 6x6x6 color cube of the xterm palette.
+pub(crate) const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
 /// An struct representing index of palette color.
 ///
 /// This struct has 1 field: `index`, which represent 8 bit color code between 0 to 255.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct PaletteColor {
     pub index: u8,
 }
@@ -30,10 +35,55 @@ impl Stylify for PaletteColor {
             match paint_type {
                 PaintType::FG => "38",
                 PaintType::BG => "48",
+                PaintType::UL => "58",
             },
             self.index
         )
     }
+
+    /// Writes the palette index code into `f` without allocating.
+    fn write_styles<W: fmt::Write>(&self, f: &mut W, paint_type: Option<&PaintType>) -> fmt::Result {
+        write!(
+            f,
+            "{};5;{}",
+            match paint_type.unwrap_or(&PaintType::FG) {
+                PaintType::FG => "38",
+                PaintType::BG => "48",
+                PaintType::UL => "58",
+            },
+            self.index
+        )
+    }
+}
+
+impl PaletteColor {
+    /// Returns the rgb value of the palette color as xterm shows it.
+    pub fn to_rgb(&self) -> Rgb {
+        match self.index {
+            0..=15 => BasicColor::from_index(self.index)
+                .and_then(|c| c.to_rgb())
+                .unwrap_or(Rgb { r: 0, g: 0, b: 0 }),
+            16..=231 => {
+                let i = self.index - 16;
+                Rgb {
+                    r: CUBE_LEVELS[(i / 36) as usize],
+                    g: CUBE_LEVELS[((i / 6) % 6) as usize],
+                    b: CUBE_LEVELS[(i % 6) as usize],
+                }
+            }
+            _ => {
+                let v = 8 + 10 * (self.index - 232);
+                Rgb { r: v, g: v, b: v }
+            }
+        }
+    }
+
+    /// Returns the nearest of the 16 basic colors.
+    ///
+    /// The indexes 0 to 15 are the basic colors themselves.
+    pub fn to_basic(&self) -> BasicColor {
+        BasicColor::from_index(self.index).unwrap_or_else(|| self.to_rgb().to_basic())
+    }
 }
 
 #[cfg(test)]
@@ -61,6 +111,13 @@ mod tests {
         assert_eq!(styles, "48;5;1")
     }
 
+    #[test]
+    fn test_make_style_ul() {
+        let color = PaletteColor { index: 160 };
+        let styles = color.make_styles(Some(&PaintType::UL));
+        assert_eq!(styles, "58;5;160")
+    }
+
     #[test]
     fn test_fg_and_bg_values() {
         let color = PaletteColor { index: 200 };
@@ -78,4 +135,22 @@ mod tests {
         assert_eq!(styles_fg, "38;5;108");
         assert_eq!(styles_bg, "48;5;108")
     }
+
+    #[test]
+    fn test_to_rgb() {
+        assert_eq!(PaletteColor { index: 1 }.to_rgb(), Rgb { r: 205, g: 0, b: 0 });
+        assert_eq!(PaletteColor { index: 16 }.to_rgb(), Rgb { r: 0, g: 0, b: 0 });
+        assert_eq!(PaletteColor { index: 196 }.to_rgb(), Rgb { r: 255, g: 0, b: 0 });
+        assert_eq!(PaletteColor { index: 67 }.to_rgb(), Rgb { r: 95, g: 135, b: 175 });
+        assert_eq!(PaletteColor { index: 232 }.to_rgb(), Rgb { r: 8, g: 8, b: 8 });
+        assert_eq!(PaletteColor { index: 255 }.to_rgb(), Rgb { r: 238, g: 238, b: 238 });
+    }
+
+    #[test]
+    fn test_to_basic() {
+        assert_eq!(PaletteColor { index: 4 }.to_basic(), BasicColor { fg: 34, bg: 44 });
+        assert_eq!(PaletteColor { index: 9 }.to_basic(), BasicColor { fg: 91, bg: 101 });
+        assert_eq!(PaletteColor { index: 196 }.to_basic(), BasicColor { fg: 91, bg: 101 });
+        assert_eq!(PaletteColor { index: 244 }.to_basic(), BasicColor { fg: 90, bg: 100 });
+    }
 }
@@ -0,0 +1,29 @@
+//! A module for choosing how painted text ends.
+//!
+//! This module provides an enum `ResetMode` that tells `StyledText::paint`
+//! whether to end the text with a full reset or with the off codes of the styles it used.
+
+// =======================================================================
+
+/// An enum representing how the styles of a painted text are turned off at its end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ResetMode {
+    /// Ends the text with `\x1b[0m`, which turns off every style, including the styles of
+    /// the text around it.
+    #[default]
+    Full,
+    /// Ends the text with the off codes of the styles it used, for example `22` for bold,
+    /// `39` for the foreground color and `49` for the background color.
+    /// The styles of the text around it stay active.
+    Targeted,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_mode() {
+        assert_eq!(ResetMode::default(), ResetMode::Full);
+    }
+}
@@ -1,20 +1,27 @@
-/// A module for creating rgb color.
-///
-/// This module provides an struct `Rgb`
-/// that represents rgb color, with r,g,b field.
-/// It also implements the `Stylify` trait for `Rgb`,
-/// which allows for generating styles based on the paint type.
+//! A module for creating rgb color.
+//!
+//! This module provides an struct `Rgb`
+//! that represents rgb color, with r,g,b field.
+//! It also implements the `Stylify` trait for `Rgb`,
+//! which allows for generating styles based on the paint type.
 
 // =======================================================================
 
-use super::{ paint_type::PaintType, Stylify };
+use std::fmt;
+
+use super::{
+    basic_color::{ BasicColor, BASIC_COLORS },
+    paint_type::PaintType,
+    palette::{ PaletteColor, CUBE_LEVELS },
+    Stylify,
+};
 
 // =======================================================================
 
 /// An struct representing rgb color.
 ///
 /// This struct has 3 field: `r`, `g` and `b` , which each of them represent 8 bit color code between 0 to 255.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Rgb {
     pub r: u8,
     pub g: u8,
@@ -32,12 +39,73 @@ impl Stylify for Rgb {
             match paint_type {
                 PaintType::FG => "38",
                 PaintType::BG => "48",
+                PaintType::UL => "58",
             },
             self.r,
             self.g,
             self.b
         )
     }
+
+    /// Writes the rgb color code into `f` without allocating.
+    fn write_styles<W: fmt::Write>(&self, f: &mut W, paint_type: Option<&PaintType>) -> fmt::Result {
+        write!(
+            f,
+            "{};2;{};{};{}",
+            match paint_type.unwrap_or(&PaintType::FG) {
+                PaintType::FG => "38",
+                PaintType::BG => "48",
+                PaintType::UL => "58",
+            },
+            self.r,
+            self.g,
+            self.b
+        )
+    }
+}
+
+impl Rgb {
+    /// Returns the nearest color of the xterm 256 color palette.
+    ///
+    /// Only the 6x6x6 color cube (16 to 231) and the grayscale ramp (232 to 255)
+    /// are considered, because the first 16 colors are changed by terminal themes.
+    pub fn to_palette(&self) -> PaletteColor {
+        let cube_index = |v: u8| {
+            CUBE_LEVELS.iter()
+                .enumerate()
+                .min_by_key(|(_, l)| (**l as i32 - v as i32).abs())
+                .map(|(i, _)| i as u8)
+                .unwrap_or(0)
+        };
+        let (r, g, b) = (cube_index(self.r), cube_index(self.g), cube_index(self.b));
+        let cube = PaletteColor { index: 16 + 36 * r + 6 * g + b };
+
+        let average = (self.r as u32 + self.g as u32 + self.b as u32) / 3;
+        let gray_step = (average.saturating_sub(8) + 5) / 10;
+        let gray = PaletteColor { index: 232 + (gray_step.min(23) as u8) };
+
+        if self.distance(&gray.to_rgb()) < self.distance(&cube.to_rgb()) {
+            gray
+        } else {
+            cube
+        }
+    }
+
+    /// Returns the nearest of the 16 basic colors.
+    pub fn to_basic(&self) -> BasicColor {
+        BASIC_COLORS.iter()
+            .min_by_key(|(_, rgb)| self.distance(rgb))
+            .map(|(c, _)| *c)
+            .unwrap_or(BasicColor { fg: 30, bg: 40 })
+    }
+
+    /// Returns the squared euclidean distance between two colors.
+    pub(crate) fn distance(&self, other: &Rgb) -> u32 {
+        let dr = self.r as i32 - other.r as i32;
+        let dg = self.g as i32 - other.g as i32;
+        let db = self.b as i32 - other.b as i32;
+        (dr * dr + dg * dg + db * db) as u32
+    }
 }
 
 #[cfg(test)]
@@ -65,6 +133,13 @@ mod tests {
         assert_eq!(styles, "48;2;255;255;43")
     }
 
+    #[test]
+    fn test_make_style_ul() {
+        let color = Rgb { r: 255, g: 0, b: 43 };
+        let styles = color.make_styles(Some(&PaintType::UL));
+        assert_eq!(styles, "58;2;255;0;43")
+    }
+
     #[test]
     fn test_fg_and_bg_values() {
         let color = Rgb { r: 78, g: 32, b: 210 };
@@ -82,4 +157,29 @@ mod tests {
         assert_eq!(styles_fg, "38;2;1;91;58");
         assert_eq!(styles_bg, "48;2;1;91;58")
     }
+
+    #[test]
+    fn test_to_palette_cube() {
+        assert_eq!(Rgb { r: 255, g: 0, b: 0 }.to_palette(), PaletteColor { index: 196 });
+        assert_eq!(Rgb { r: 0, g: 0, b: 255 }.to_palette(), PaletteColor { index: 21 });
+        assert_eq!(Rgb { r: 95, g: 135, b: 175 }.to_palette(), PaletteColor { index: 67 });
+        assert_eq!(Rgb { r: 30, g: 144, b: 255 }.to_palette(), PaletteColor { index: 33 });
+    }
+
+    #[test]
+    fn test_to_palette_gray() {
+        assert_eq!(Rgb { r: 128, g: 128, b: 128 }.to_palette(), PaletteColor { index: 244 });
+        assert_eq!(Rgb { r: 10, g: 10, b: 10 }.to_palette(), PaletteColor { index: 232 });
+        assert_eq!(Rgb { r: 0, g: 0, b: 0 }.to_palette(), PaletteColor { index: 16 });
+        assert_eq!(Rgb { r: 255, g: 255, b: 255 }.to_palette(), PaletteColor { index: 231 });
+    }
+
+    #[test]
+    fn test_to_basic() {
+        assert_eq!(Rgb { r: 250, g: 10, b: 10 }.to_basic(), BasicColor { fg: 91, bg: 101 });
+        assert_eq!(Rgb { r: 180, g: 0, b: 0 }.to_basic(), BasicColor { fg: 31, bg: 41 });
+        assert_eq!(Rgb { r: 0, g: 0, b: 0 }.to_basic(), BasicColor { fg: 30, bg: 40 });
+        assert_eq!(Rgb { r: 120, g: 125, b: 130 }.to_basic(), BasicColor { fg: 90, bg: 100 });
+        assert_eq!(Rgb { r: 250, g: 250, b: 250 }.to_basic(), BasicColor { fg: 97, bg: 107 });
+    }
 }
@@ -0,0 +1,29 @@
+//! A module for choosing how the escape codes of a painted text are written.
+//!
+//! This module provides an enum `SgrMode` that tells `StyledText::paint`
+//! whether to write one escape code per style or to combine them into one.
+
+// =======================================================================
+
+/// An enum representing how the SGR parameters of a painted text are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SgrMode {
+    /// Writes one escape code per style, for example `\x1b[31m\x1b[1m\x1b[4m`.
+    #[default]
+    Separate,
+    /// Writes all the parameters in one escape code, for example `\x1b[31;1;4m`.
+    ///
+    /// Parameters that are overridden later are dropped: of two foreground colors only
+    /// the last one is written, and nothing before a `reset` is written at all.
+    Combined,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_mode() {
+        assert_eq!(SgrMode::default(), SgrMode::Separate);
+    }
+}
@@ -0,0 +1,308 @@
+//! A module for the resolved style of a piece of text.
+//!
+//! This module provides a struct `SpanStyle` that holds what a terminal shows
+//! after a list of styles has been applied: the foreground color, the background color,
+//! the underline color and style, and the active formatters.
+
+// =======================================================================
+
+use super::{
+    color::Color,
+    formatter::{ Formatter, FormatterSet },
+    paint_type::PaintType,
+    underline_style::{ self, UnderlineStyle },
+    Styles,
+    Stylify,
+};
+
+// =======================================================================
+
+/// A struct representing the resolved style of a piece of text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SpanStyle {
+    /// The foreground color, `None` is the default color of the terminal.
+    pub fg: Option<Color>,
+    /// The background color, `None` is the default color of the terminal.
+    pub bg: Option<Color>,
+    /// The underline color, `None` is the color of the text.
+    pub ul: Option<Color>,
+    /// The underline style, `None` is the underline of the formatters if there is one.
+    pub underline_style: Option<UnderlineStyle>,
+    /// The active formatters.
+    pub formatters: FormatterSet,
+}
+
+impl SpanStyle {
+    /// Returns `true` if the style has no color and no formatter.
+    pub fn is_plain(&self) -> bool {
+        self.fg.is_none() &&
+            self.bg.is_none() &&
+            self.ul.is_none() &&
+            self.underline_style.is_none() &&
+            self.formatters.is_empty()
+    }
+
+    /// Returns the parameters that set the style on a terminal with the default style.
+    ///
+    /// The formatters and the underline style come first, then the foreground,
+    /// the background and the underline color.
+    pub fn codes(&self) -> Vec<String> {
+        let mut codes: Vec<String> = self.formatters
+            .iter()
+            .map(|f| f.make_styles(None))
+            .collect();
+        if let Some(u) = &self.underline_style {
+            codes.push(u.make_styles(None));
+        }
+        if let Some(c) = &self.fg {
+            codes.push(c.make_styles(Some(&PaintType::FG)));
+        }
+        if let Some(c) = &self.bg {
+            codes.push(c.make_styles(Some(&PaintType::BG)));
+        }
+        if let Some(c) = &self.ul {
+            codes.push(c.make_styles(Some(&PaintType::UL)));
+        }
+        codes
+    }
+
+    /// Returns the shortest list of parameters that changes this style into the `target` style.
+    ///
+    /// Formatters that are gone are turned off with their off codes and formatters that
+    /// share an off code with them are set again. An underline style replaces a plain underline
+    /// without turning it off first. If a reset followed by the whole `target`
+    /// style is shorter, that is returned instead. Equal styles need no parameter.
+    pub fn transition_to(&self, target: &SpanStyle) -> Vec<String> {
+        if self == target {
+            return vec![];
+        }
+        let mut reset = vec!["0".to_string()];
+        reset.extend(target.codes());
+
+        let mut off_codes: Vec<u8> = self.formatters
+            .iter()
+            .filter(|f| !target.formatters.contains(f))
+            .filter(|f| target.underline_style.is_none() || !matches!(f.code, 4 | 21))
+            .filter_map(|f| f.off_code())
+            .collect();
+        off_codes.sort();
+        off_codes.dedup();
+        let mut codes: Vec<String> = off_codes
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        for f in target.formatters.iter() {
+            let turned_off = f.off_code().is_some_and(|c| off_codes.contains(&c));
+            if turned_off || !self.formatters.contains(&f) {
+                codes.push(f.make_styles(None));
+            }
+        }
+        let underline_off = off_codes.contains(&24);
+        if self.underline_style != target.underline_style || underline_off {
+            match &target.underline_style {
+                Some(u) => codes.push(u.make_styles(None)),
+                None if self.underline_style.is_some() && !underline_off => {
+                    let underlined = target.formatters.iter().any(|f| underline_style::is_underline_formatter(&f));
+                    if !underlined {
+                        codes.push("24".to_string());
+                    }
+                }
+                None => {}
+            }
+        }
+        if self.fg != target.fg {
+            codes.push(match &target.fg {
+                Some(c) => c.make_styles(Some(&PaintType::FG)),
+                None => "39".to_string(),
+            });
+        }
+        if self.bg != target.bg {
+            codes.push(match &target.bg {
+                Some(c) => c.make_styles(Some(&PaintType::BG)),
+                None => "49".to_string(),
+            });
+        }
+        if self.ul != target.ul {
+            codes.push(match &target.ul {
+                Some(c) => c.make_styles(Some(&PaintType::UL)),
+                None => "59".to_string(),
+            });
+        }
+
+        if reset.join(";").len() < codes.join(";").len() {
+            reset
+        } else {
+            codes
+        }
+    }
+
+    /// Returns the codes that turn off every color and formatter of the style.
+    ///
+    /// Formatters that share an off code, like bold and faint, add it only once.
+    /// `39` is added for a foreground color, `49` for a background color
+    /// and `59` for an underline color.
+    pub fn off_codes(&self) -> Vec<u8> {
+        let mut codes: Vec<u8> = self.off_formatters()
+            .iter()
+            .map(|f| f.code)
+            .collect();
+        if self.fg.is_some() {
+            codes.push(39);
+        }
+        if self.bg.is_some() {
+            codes.push(49);
+        }
+        if self.ul.is_some() {
+            codes.push(59);
+        }
+        codes
+    }
+
+    /// Returns the off codes of the formatters and the underline style as a set, ordered by their code.
+    pub(crate) fn off_formatters(&self) -> FormatterSet {
+        let mut off = FormatterSet::new();
+        for code in self.formatters.iter().filter_map(|f| f.off_code()) {
+            off.insert(&Formatter { code });
+        }
+        if self.underline_style.is_some() {
+            off.insert(&Formatter { code: 24 });
+        }
+        off
+    }
+
+    /// Applies a style like a terminal would.
+    ///
+    /// Colors are set as foreground, background or underline color based on the `paint_type`,
+    /// `RESET` clears everything and the other formatters are applied to `formatters`.
+    /// An underline style replaces the single and double underline and the other way around.
+    pub fn apply(&mut self, style: &Styles, paint_type: &PaintType) {
+        match style {
+            Styles::StyleFormatter(f) if f.code == 0 => {
+                *self = SpanStyle::default();
+            }
+            Styles::StyleFormatter(f) => {
+                self.formatters.apply(f);
+                if underline_style::is_underline_formatter(f) {
+                    self.underline_style = None;
+                }
+            }
+            Styles::StyleUnderline(u) => {
+                self.formatters.remove(&Formatter { code: 4 });
+                self.formatters.remove(&Formatter { code: 21 });
+                self.underline_style = Some(*u);
+            }
+            Styles::StylePaintType(_) => {}
+            _ => {
+                let color = Color::from_styles(style);
+                match paint_type {
+                    PaintType::FG => {
+                        self.fg = color;
+                    }
+                    PaintType::BG => {
+                        self.bg = color;
+                    }
+                    PaintType::UL => {
+                        self.ul = color;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styles::{ basic_color, formatter, rgb::Rgb };
+
+    #[test]
+    fn test_apply() {
+        let mut style = SpanStyle::default();
+        assert!(style.is_plain());
+
+        style.apply(&basic_color::RED, &PaintType::FG);
+        style.apply(&Styles::StyleRgb(Rgb { r: 1, g: 2, b: 3 }), &PaintType::BG);
+        style.apply(&formatter::BOLD, &PaintType::FG);
+        assert_eq!(style.fg, Color::from_styles(&basic_color::RED));
+        assert_eq!(style.bg, Some(Color::Rgb(Rgb { r: 1, g: 2, b: 3 })));
+        assert!(style.formatters.contains(&Formatter { code: 1 }));
+        assert!(!style.is_plain());
+
+        style.apply(&formatter::RESET, &PaintType::FG);
+        assert!(style.is_plain());
+    }
+
+    #[test]
+    fn test_off_codes() {
+        let mut style = SpanStyle::default();
+        assert!(style.off_codes().is_empty());
+
+        style.apply(&formatter::OVERLINE, &PaintType::FG);
+        style.apply(&formatter::FAINT, &PaintType::FG);
+        style.apply(&formatter::BOLD, &PaintType::FG);
+        style.apply(&formatter::ITALIC, &PaintType::FG);
+        style.apply(&basic_color::RED, &PaintType::BG);
+        assert_eq!(style.off_codes(), vec![22, 23, 55, 49]);
+
+        style.apply(&basic_color::RED, &PaintType::FG);
+        style.apply(&formatter::SLOW_BLINK, &PaintType::FG);
+        style.apply(&formatter::UNDERLINE, &PaintType::FG);
+        assert_eq!(style.off_codes(), vec![22, 23, 24, 25, 55, 39, 49]);
+    }
+
+    #[test]
+    fn test_codes() {
+        let mut style = SpanStyle::default();
+        assert!(style.codes().is_empty());
+        style.apply(&Styles::StyleRgb(Rgb { r: 1, g: 2, b: 3 }), &PaintType::BG);
+        style.apply(&basic_color::RED, &PaintType::FG);
+        style.apply(&formatter::UNDERLINE, &PaintType::FG);
+        style.apply(&formatter::BOLD, &PaintType::FG);
+        assert_eq!(style.codes(), vec!["1", "4", "31", "48;2;1;2;3"]);
+    }
+
+    #[test]
+    fn test_transition_to() {
+        let mut bold_red = SpanStyle::default();
+        bold_red.apply(&formatter::BOLD, &PaintType::FG);
+        bold_red.apply(&basic_color::RED, &PaintType::FG);
+        let mut bold_faint = SpanStyle::default();
+        bold_faint.apply(&formatter::BOLD, &PaintType::FG);
+        bold_faint.apply(&formatter::FAINT, &PaintType::FG);
+        let mut faint = SpanStyle::default();
+        faint.apply(&formatter::FAINT, &PaintType::FG);
+        let plain = SpanStyle::default();
+
+        assert!(bold_red.transition_to(&bold_red).is_empty());
+        assert_eq!(plain.transition_to(&bold_red), vec!["1", "31"]);
+        assert_eq!(bold_red.transition_to(&plain), vec!["0"]);
+        assert_eq!(bold_red.transition_to(&bold_faint), vec!["2", "39"]);
+        assert_eq!(bold_faint.transition_to(&faint), vec!["0", "2"]);
+        bold_faint.apply(&basic_color::RED, &PaintType::FG);
+        faint.apply(&basic_color::RED, &PaintType::FG);
+        assert_eq!(bold_faint.transition_to(&faint), vec!["22", "2"]);
+    }
+
+    #[test]
+    fn test_underline_style_and_color() {
+        let mut style = SpanStyle::default();
+        style.apply(&formatter::UNDERLINE, &PaintType::FG);
+        style.apply(&underline_style::CURLY_UNDERLINE, &PaintType::FG);
+        style.apply(&basic_color::RED, &PaintType::UL);
+        assert!(!style.formatters.contains(&Formatter { code: 4 }));
+        assert_eq!(style.codes(), vec!["4:3", "58;5;1"]);
+        assert_eq!(style.off_codes(), vec![24, 59]);
+
+        let mut plain_underline = style;
+        plain_underline.apply(&formatter::UNDERLINE, &PaintType::FG);
+        assert_eq!(plain_underline.underline_style, None);
+        assert_eq!(style.transition_to(&plain_underline), vec!["4"]);
+        assert_eq!(plain_underline.transition_to(&style), vec!["4:3"]);
+
+        let mut no_underline = style;
+        no_underline.apply(&formatter::NO_UNDERLINE, &PaintType::FG);
+        no_underline.apply(&formatter::BOLD, &PaintType::FG);
+        assert_eq!(style.transition_to(&no_underline), vec!["1", "24"]);
+        assert_eq!(no_underline.transition_to(&style), vec!["22", "4:3"]);
+    }
+}
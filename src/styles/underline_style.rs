@@ -0,0 +1,103 @@
+//! A module for the underline styles of modern terminals.
+//!
+//! This module provides an enum `UnderlineStyle` for the curly, dotted and dashed underlines
+//! that terminals like kitty, WezTerm and VTE support with the `4:x` parameter.
+//! It also implements the `Stylify` trait for `UnderlineStyle`.
+
+// =======================================================================
+
+use std::fmt;
+
+use super::{ formatter::Formatter, paint_type::PaintType, Styles, Stylify };
+
+// =======================================================================
+
+/// An enum representing an underline style written as a `4:x` parameter.
+///
+/// The single and double underlines are the `UNDERLINE` and `DOUBLE_UNDERLINE` formatters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnderlineStyle {
+    /// A curly underline, `4:3`.
+    Curly,
+    /// A dotted underline, `4:4`.
+    Dotted,
+    /// A dashed underline, `4:5`.
+    Dashed,
+}
+
+impl UnderlineStyle {
+    /// Returns the sub parameter of the style, the `x` of `4:x`.
+    pub const fn code(&self) -> u8 {
+        match self {
+            UnderlineStyle::Curly => 3,
+            UnderlineStyle::Dotted => 4,
+            UnderlineStyle::Dashed => 5,
+        }
+    }
+
+    /// Returns the style of a sub parameter, or `None` if it is not one of the styles.
+    pub const fn from_code(code: u8) -> Option<UnderlineStyle> {
+        match code {
+            3 => Some(UnderlineStyle::Curly),
+            4 => Some(UnderlineStyle::Dotted),
+            5 => Some(UnderlineStyle::Dashed),
+            _ => None,
+        }
+    }
+
+    /// Returns the formatter that terminals without underline styles show instead.
+    pub const fn fallback(&self) -> Formatter {
+        Formatter { code: 4 }
+    }
+}
+
+impl Stylify for UnderlineStyle {
+    /// Returns a string representation of the underline style.
+    ///
+    /// The `PaintType` is not any involved in this method!
+    fn make_styles(&self, _paint_type: Option<&PaintType>) -> String {
+        format!("4:{}", self.code())
+    }
+
+    /// Writes the underline style into `f` without allocating.
+    fn write_styles<W: fmt::Write>(&self, f: &mut W, _paint_type: Option<&PaintType>) -> fmt::Result {
+        write!(f, "4:{}", self.code())
+    }
+}
+
+pub const CURLY_UNDERLINE: Styles = Styles::StyleUnderline(UnderlineStyle::Curly);
+pub const DOTTED_UNDERLINE: Styles = Styles::StyleUnderline(UnderlineStyle::Dotted);
+pub const DASHED_UNDERLINE: Styles = Styles::StyleUnderline(UnderlineStyle::Dashed);
+
+/// Returns `true` if the formatter replaces or turns off an underline style,
+/// like the single and double underlines and their off code `24`.
+pub(crate) const fn is_underline_formatter(formatter: &Formatter) -> bool {
+    matches!(formatter.code, 4 | 21 | 24)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_styles() {
+        assert_eq!(UnderlineStyle::Curly.make_styles(None), "4:3");
+        assert_eq!(UnderlineStyle::Dotted.make_styles(Some(&PaintType::BG)), "4:4");
+        assert_eq!(UnderlineStyle::Dashed.make_styles(Some(&PaintType::UL)), "4:5");
+    }
+
+    #[test]
+    fn test_from_code() {
+        for style in [UnderlineStyle::Curly, UnderlineStyle::Dotted, UnderlineStyle::Dashed] {
+            assert_eq!(UnderlineStyle::from_code(style.code()), Some(style));
+        }
+        assert_eq!(UnderlineStyle::from_code(1), None);
+    }
+
+    #[test]
+    fn test_underline_constants() {
+        assert_eq!(CURLY_UNDERLINE, Styles::StyleUnderline(UnderlineStyle::Curly));
+        assert_eq!(DOTTED_UNDERLINE, Styles::StyleUnderline(UnderlineStyle::Dotted));
+        assert_eq!(DASHED_UNDERLINE, Styles::StyleUnderline(UnderlineStyle::Dashed));
+    }
+}
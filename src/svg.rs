@@ -0,0 +1,442 @@
+//! A module for rendering styled text as an SVG image of a terminal.
+//!
+//! This module provides a struct `SvgRenderer` that draws a `StyledText`, a `StyledDocument`
+//! or any painted string on a monospaced grid, optionally inside a window with a title bar.
+//! Every column of the grid is as wide as a half-width character, so wide characters take two.
+//! Each piece of text is stretched or squeezed to its cells with `textLength`, so the glyphs
+//! stay on the grid whatever the advance width of the font is.
+//! The image shows every style `paint` can write: colors from a `ColorScheme`, the formatters,
+//! the underline styles and the underline color.
+
+// =======================================================================
+
+use crate::{
+    ansi_code::parser::AnsiParser,
+    html::{ escape_html, has_link_scheme },
+    styles::{
+        color_scheme::ColorScheme,
+        formatter::Formatter,
+        rgb::Rgb,
+        span_style::SpanStyle,
+        underline_style::UnderlineStyle,
+    },
+    visible_width,
+    StyledDocument,
+    StyledText,
+};
+
+// =======================================================================
+
+/// The height of the title bar of the window.
+const TITLE_BAR_HEIGHT: f64 = 32.0;
+
+/// The colors of the three buttons in the title bar.
+const BUTTON_COLORS: [&str; 3] = ["#ff5f56", "#ffbd2e", "#27c93f"];
+
+/// The width of a column relative to the font size, which fits most monospaced fonts.
+const COLUMN_WIDTH: f64 = 0.6;
+
+/// A struct for rendering styled text as an SVG image.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ styled, svg::SvgRenderer };
+/// let svg = SvgRenderer::new().title("cargo test").render(styled("ok").green().bold());
+/// assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+/// assert!(svg.contains("<text x=\"0\" y=\"14\" fill=\"#00cd00\" font-weight=\"bold\" textLength=\"16.8\""));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgRenderer {
+    scheme: ColorScheme,
+    window: bool,
+    title: Option<String>,
+    font_family: String,
+    font_size: f64,
+    line_height: f64,
+    padding: f64,
+}
+
+impl Default for SvgRenderer {
+    fn default() -> Self {
+        SvgRenderer::new()
+    }
+}
+
+impl SvgRenderer {
+    /// Returns a renderer with a window, the xterm color scheme and a 14 pixel monospace font.
+    pub fn new() -> Self {
+        SvgRenderer {
+            scheme: ColorScheme::xterm(),
+            window: true,
+            title: None,
+            font_family: "ui-monospace, Menlo, Consolas, monospace".to_string(),
+            font_size: 14.0,
+            line_height: 1.3,
+            padding: 12.0,
+        }
+    }
+
+    /// Sets the color scheme that basic and palette colors and the default colors are shown with.
+    pub fn color_scheme(&mut self, scheme: ColorScheme) -> &mut Self {
+        self.scheme = scheme;
+        self
+    }
+
+    /// Sets whether the text is drawn inside a window with a title bar, the default is `true`.
+    ///
+    /// **without the window the title is only kept as the `<title>` of the image**
+    pub fn window(&mut self, window: bool) -> &mut Self {
+        self.window = window;
+        self
+    }
+
+    /// Sets the title that is shown in the title bar of the window.
+    pub fn title(&mut self, title: &str) -> &mut Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// Sets the font family, a list of fonts like in CSS.
+    pub fn font_family(&mut self, family: &str) -> &mut Self {
+        self.font_family = family.to_string();
+        self
+    }
+
+    /// Sets the font size in pixels, the grid grows with it.
+    pub fn font_size(&mut self, size: f64) -> &mut Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Sets the height of a line relative to the font size, the default is `1.3`.
+    pub fn line_height(&mut self, height: f64) -> &mut Self {
+        self.line_height = height;
+        self
+    }
+
+    /// Sets the space around the text in pixels.
+    pub fn padding(&mut self, padding: f64) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Renders a styled text, the color level of the text is ignored.
+    pub fn render(&self, styled: &StyledText) -> String {
+        let mut doc = StyledDocument::new();
+        doc.push(styled);
+        self.render_document(&doc)
+    }
+
+    /// Renders a document.
+    pub fn render_document(&self, doc: &StyledDocument) -> String {
+        self.render_spans(doc.spans().into_iter().map(|(text, style, link)| (text, style, link.map(|l| l.url()))))
+    }
+
+    /// Renders a painted string, like the output of a command.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::svg::SvgRenderer;
+    /// let svg = SvgRenderer::new().window(false).render_ansi("\x1b[4:3;58;5;1mtypo\x1b[0m");
+    /// assert!(svg.contains("stroke=\"#cd0000\""));
+    /// ```
+    pub fn render_ansi(&self, painted: &str) -> String {
+        let spans = AnsiParser::new().parse(painted);
+        self.render_spans(spans.iter().map(|s| (s.text.as_str(), s.style, s.link.as_deref())))
+    }
+
+    /// Renders pieces of text with their styles and links, a newline starts the next row of the grid.
+    ///
+    /// Links with one of the schemes `HtmlRenderer::convert` allows become `<a>` elements.
+    fn render_spans<'a>(&self, spans: impl Iterator<Item = (&'a str, SpanStyle, Option<&'a str>)>) -> String {
+        let mut lines: Vec<Vec<(&str, SpanStyle, Option<&str>)>> = vec![vec![]];
+        for (text, style, link) in spans {
+            for (i, line) in text.split('\n').enumerate() {
+                if i > 0 {
+                    lines.push(vec![]);
+                }
+                if let Some(last) = lines.last_mut().filter(|_| !line.is_empty()) {
+                    last.push((line, style, link));
+                }
+            }
+        }
+        let columns = lines
+            .iter()
+            .map(|line| line.iter().map(|(text, _, _)| visible_width(text)).sum::<usize>())
+            .max()
+            .unwrap_or(0);
+
+        let top = if self.window { TITLE_BAR_HEIGHT } else { 0.0 };
+        let width = self.column_width() * columns as f64 + 2.0 * self.padding;
+        let height = top + self.row_height() * lines.len() as f64 + 2.0 * self.padding;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" \
+             font-family=\"{}\" font-size=\"{}\" xml:space=\"preserve\">",
+            escape_xml(&self.font_family),
+            num(self.font_size),
+            w = num(width),
+            h = num(height)
+        );
+        if let Some(title) = &self.title {
+            svg.push_str(&format!("<title>{}</title>", escape_xml(title)));
+        }
+        let radius = if self.window { 6.0 } else { 0.0 };
+        svg.push_str(&format!(
+            "<rect width=\"100%\" height=\"100%\" rx=\"{}\" fill=\"{}\"/>",
+            num(radius),
+            self.scheme.background.to_hex()
+        ));
+        if self.window {
+            self.write_title_bar(&mut svg, width);
+        }
+        svg.push_str(&format!("<g transform=\"translate({} {})\">", num(self.padding), num(top + self.padding)));
+        for (row, line) in lines.iter().enumerate() {
+            let mut column = 0;
+            for (text, style, link) in line {
+                let cells = visible_width(text);
+                let link = link.filter(|url| has_link_scheme(url));
+                if let Some(url) = link {
+                    svg.push_str(&format!("<a href=\"{}\">", escape_xml(url)));
+                }
+                self.write_span(&mut svg, text, style, row, column, cells);
+                if link.is_some() {
+                    svg.push_str("</a>");
+                }
+                column += cells;
+            }
+        }
+        svg.push_str("</g></svg>");
+        svg
+    }
+
+    /// Writes the three buttons and the title of the window.
+    fn write_title_bar(&self, svg: &mut String, width: f64) {
+        for (i, color) in BUTTON_COLORS.iter().enumerate() {
+            svg.push_str(&format!("<circle cx=\"{}\" cy=\"16\" r=\"6\" fill=\"{}\"/>", 18 + 20 * i, color));
+        }
+        if let Some(title) = &self.title {
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"20\" text-anchor=\"middle\" fill=\"{}\" opacity=\"0.6\">{}</text>",
+                num(width / 2.0),
+                self.scheme.foreground.to_hex(),
+                escape_xml(title)
+            ));
+        }
+    }
+
+    /// Writes the background, the text and the lines of one piece of text.
+    ///
+    /// Inverse swaps the foreground and background color and conceal hides the text.
+    fn write_span(&self, svg: &mut String, text: &str, style: &SpanStyle, row: usize, column: usize, cells: usize) {
+        let has = |code: u8| style.formatters.contains(&Formatter { code });
+        let mut fg = style.fg.map(|c| self.scheme.resolve(&c));
+        let mut bg = style.bg.map(|c| self.scheme.resolve(&c));
+        if has(7) {
+            (fg, bg) = (Some(bg.unwrap_or(self.scheme.background)), Some(fg.unwrap_or(self.scheme.foreground)));
+        }
+        let fg = fg.unwrap_or(self.scheme.foreground);
+        let x = self.column_width() * column as f64;
+        let y = self.row_height() * row as f64;
+        let w = self.column_width() * cells as f64;
+        let h = self.row_height();
+        let baseline = y + self.font_size;
+
+        if let Some(bg) = bg {
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                num(x), num(y), num(w), num(h), bg.to_hex()
+            ));
+        }
+        if has(51) || has(52) {
+            let radius = if has(52) { h / 2.0 } else { 0.0 };
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" fill=\"none\" stroke=\"{}\"/>",
+                num(x + 0.5), num(y + 0.5), num(w - 1.0), num(h - 1.0), num(radius), fg.to_hex()
+            ));
+        }
+        if has(8) {
+            return;
+        }
+
+        let (text_y, font_size) = match (has(73), has(74)) {
+            (true, _) => (baseline - self.font_size * 0.35, Some(self.font_size * 0.7)),
+            (_, true) => (baseline + self.font_size * 0.2, Some(self.font_size * 0.7)),
+            _ => (baseline, None),
+        };
+        let mut attributes = format!("x=\"{}\" y=\"{}\" fill=\"{}\"", num(x), num(text_y), fg.to_hex());
+        if let Some(font_size) = font_size {
+            attributes.push_str(&format!(" font-size=\"{}\"", num(font_size)));
+        }
+        if has(1) {
+            attributes.push_str(" font-weight=\"bold\"");
+        }
+        if has(3) {
+            attributes.push_str(" font-style=\"italic\"");
+        }
+        if has(2) {
+            attributes.push_str(" opacity=\"0.7\"");
+        }
+        if cells > 0 {
+            // keeps the glyphs on the grid, whatever the advance width of the font is
+            attributes.push_str(&format!(" textLength=\"{}\" lengthAdjust=\"spacingAndGlyphs\"", num(w)));
+        }
+        let blink = match (has(5), has(6)) {
+            (_, true) => "<animate attributeName=\"opacity\" values=\"1;0\" dur=\"0.5s\" calcMode=\"discrete\" repeatCount=\"indefinite\"/>",
+            (true, _) => "<animate attributeName=\"opacity\" values=\"1;0\" dur=\"1s\" calcMode=\"discrete\" repeatCount=\"indefinite\"/>",
+            _ => "",
+        };
+        svg.push_str(&format!("<text {}>{}{}</text>", attributes, escape_xml(text), blink));
+
+        let underline_color = style.ul.map(|c| self.scheme.resolve(&c)).unwrap_or(fg);
+        let underline_y = baseline + self.font_size * 0.18;
+        match style.underline_style {
+            Some(UnderlineStyle::Curly) => {
+                let amplitude = self.font_size * 0.12;
+                let mut path = format!("M{} {}", num(x), num(underline_y));
+                for _ in 0..cells {
+                    path.push_str(&format!(
+                        " q{} {} {} 0 t{} 0",
+                        num(self.column_width() / 4.0),
+                        num(-amplitude),
+                        num(self.column_width() / 2.0),
+                        num(self.column_width() / 2.0)
+                    ));
+                }
+                svg.push_str(&format!("<path d=\"{}\" fill=\"none\" stroke=\"{}\"/>", path, underline_color.to_hex()));
+            }
+            Some(UnderlineStyle::Dotted) => self.write_line(svg, x, w, underline_y, &underline_color, " stroke-dasharray=\"1 2\""),
+            Some(UnderlineStyle::Dashed) => self.write_line(svg, x, w, underline_y, &underline_color, " stroke-dasharray=\"4 2\""),
+            None if has(21) => {
+                self.write_line(svg, x, w, underline_y - 1.0, &underline_color, "");
+                self.write_line(svg, x, w, underline_y + 1.5, &underline_color, "");
+            }
+            None if has(4) => self.write_line(svg, x, w, underline_y, &underline_color, ""),
+            None => {}
+        }
+        if has(53) {
+            self.write_line(svg, x, w, y + 1.0, &fg, "");
+        }
+        if has(9) {
+            self.write_line(svg, x, w, baseline - self.font_size * 0.3, &fg, "");
+        }
+    }
+
+    /// Writes a horizontal line.
+    fn write_line(&self, svg: &mut String, x: f64, width: f64, y: f64, color: &Rgb, extra: &str) {
+        svg.push_str(&format!(
+            "<line x1=\"{}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\" stroke=\"{}\"{}/>",
+            num(x),
+            num(x + width),
+            color.to_hex(),
+            extra,
+            y = num(y)
+        ));
+    }
+
+    /// Returns the width of a column in pixels.
+    fn column_width(&self) -> f64 {
+        self.font_size * COLUMN_WIDTH
+    }
+
+    /// Returns the height of a row in pixels.
+    fn row_height(&self) -> f64 {
+        self.font_size * self.line_height
+    }
+}
+
+/// Returns the text escaped for XML, without the characters XML 1.0 does not allow.
+///
+/// Control characters like `\x07` or `\x1b` and `U+FFFE` and `U+FFFF` are dropped,
+/// because they would make the SVG invalid.
+fn escape_xml(text: &str) -> String {
+    let valid: String = text
+        .chars()
+        .filter(|c| {
+            matches!(c, '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..)
+        })
+        .collect();
+    escape_html(&valid)
+}
+
+/// Returns a number with at most two decimals and without trailing zeros.
+fn num(value: f64) -> String {
+    let formatted = format!("{:.2}", value);
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styled;
+
+    fn plain() -> SvgRenderer {
+        let mut renderer = SvgRenderer::new();
+        renderer.window(false).padding(0.0).font_size(10.0).line_height(2.0);
+        renderer
+    }
+
+    #[test]
+    fn render_grid_size() {
+        let svg = plain().render_ansi("ab\n日本");
+        assert!(svg.contains("width=\"24\" height=\"40\""));
+        assert!(svg.contains(
+            "<text x=\"0\" y=\"30\" fill=\"#e5e5e5\" textLength=\"24\" lengthAdjust=\"spacingAndGlyphs\">日本</text>"
+        ));
+    }
+
+    #[test]
+    fn render_window() {
+        let svg = SvgRenderer::new().title("a & b").render(&styled("x"));
+        assert!(svg.contains("<title>a &amp; b</title>"));
+        assert!(svg.contains("<circle cx=\"58\" cy=\"16\" r=\"6\" fill=\"#27c93f\"/>"));
+        assert!(svg.contains("translate(12 44)"));
+    }
+
+    #[test]
+    fn render_drops_invalid_xml_characters() {
+        let svg = plain().title("a\x07").render(&styled("b\x07c\u{FFFF} & d"));
+        assert!(svg.contains("<title>a</title>"));
+        assert!(svg.contains(">bc &amp; d</text>"));
+        assert!(!svg.contains('\x07'));
+    }
+
+    #[test]
+    fn render_links() {
+        let svg = plain().render(styled("a").link("https://x.y"));
+        assert!(svg.contains("<a href=\"https://x.y\"><text x=\"0\" y=\"10\" fill=\"#e5e5e5\" textLength=\"6\" lengthAdjust=\"spacingAndGlyphs\">a</text></a>"));
+        let svg = plain().render_ansi("\x1b]8;;javascript:x\x1b\\b\x1b]8;;\x1b\\");
+        assert!(!svg.contains("<a "));
+    }
+
+    #[test]
+    fn render_colors_and_inverse() {
+        let svg = plain().render(styled("a").red().fg().blue().bg().inverse());
+        assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"6\" height=\"20\" fill=\"#cd0000\"/>"));
+        assert!(svg.contains("fill=\"#0000ee\" textLength=\"6\" lengthAdjust=\"spacingAndGlyphs\">a</text>"));
+    }
+
+    #[test]
+    fn render_formatters() {
+        let svg = plain().render(styled("a").italic().faint().strikethrough().overline().slow_blink());
+        assert!(svg.contains("<text x=\"0\" y=\"10\" fill=\"#e5e5e5\" font-style=\"italic\" opacity=\"0.7\" textLength=\"6\" lengthAdjust=\"spacingAndGlyphs\">a<animate"));
+        assert!(svg.contains("dur=\"1s\""));
+        assert!(svg.contains("<line x1=\"0\" y1=\"7\" x2=\"6\" y2=\"7\" stroke=\"#e5e5e5\"/>"));
+        assert!(svg.contains("<line x1=\"0\" y1=\"1\" x2=\"6\" y2=\"1\" stroke=\"#e5e5e5\"/>"));
+    }
+
+    #[test]
+    fn render_underlines() {
+        let svg = plain().render(styled("a").dashed_underline().rgb(1, 2, 3).ul());
+        assert!(svg.contains("<line x1=\"0\" y1=\"11.8\" x2=\"6\" y2=\"11.8\" stroke=\"#010203\" stroke-dasharray=\"4 2\"/>"));
+        let svg = plain().render(styled("ab").curly_underline());
+        assert!(svg.contains("<path d=\"M0 11.8 q1.5 -1.2 3 0 t3 0 q1.5 -1.2 3 0 t3 0\""));
+    }
+
+    #[test]
+    fn render_conceal_and_scripts() {
+        let svg = plain().render(styled("secret").conceal());
+        assert!(!svg.contains("secret"));
+        let svg = plain().render(styled("2").superscript().bold());
+        assert!(svg.contains("<text x=\"0\" y=\"6.5\" fill=\"#e5e5e5\" font-size=\"7\" font-weight=\"bold\" textLength=\"6\" lengthAdjust=\"spacingAndGlyphs\">2</text>"));
+    }
+}
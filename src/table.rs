@@ -0,0 +1,744 @@
+//! A module for printing tables of styled text.
+//!
+//! This module provides a struct `Table` that lays out rows of `Cell` in aligned columns.
+//! Cells hold plain text, painted text or a `StyledText`, and can have a `Style` of their own.
+//! The widths are measured with `visible_width`, so escape codes take no space and wide
+//! characters take two columns. Text that does not fit is wrapped or truncated with an
+//! ellipsis, and the styles of the text are carried over the borders and lines they are split at.
+
+// =======================================================================
+
+use std::fmt;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    ansi_code::parser::{ paint_spans, parse, Span },
+    styles::{ color_level::ColorLevel, paint_type::PaintType, span_style::SpanStyle, Styles },
+    width::visible_width,
+    Style,
+    StyledText,
+};
+
+// =======================================================================
+
+/// An enum representing where the text of a cell is put when it is narrower than its column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Align {
+    /// At the left side.
+    #[default]
+    Left,
+    /// In the middle, a column that is left over goes to the right side.
+    Center,
+    /// At the right side, like numbers.
+    Right,
+}
+
+/// An enum representing what happens to text that is wider than its column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Overflow {
+    /// The text is wrapped at spaces onto more lines, words longer than a line are split.
+    #[default]
+    Wrap,
+    /// The text is cut and ends with `…`.
+    Truncate,
+}
+
+/// An enum representing the characters the table is drawn with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Border {
+    /// `+`, `-` and `|`, for any terminal.
+    Ascii,
+    /// Thin lines with rounded corners.
+    #[default]
+    Rounded,
+    /// Thick lines.
+    Heavy,
+    /// Double lines.
+    Double,
+    /// No lines, the columns are only separated by their padding.
+    None,
+}
+
+/// A struct holding the characters of a border.
+///
+/// The corners and crossings are in the order left, middle, right.
+struct BorderChars {
+    horizontal: &'static str,
+    vertical: &'static str,
+    top: [&'static str; 3],
+    middle: [&'static str; 3],
+    bottom: [&'static str; 3],
+}
+
+impl Border {
+    /// Returns the characters of the border, or `None` for `Border::None`.
+    fn chars(&self) -> Option<BorderChars> {
+        let (horizontal, vertical, top, middle, bottom) = match self {
+            Border::Ascii => ("-", "|", ["+", "+", "+"], ["+", "+", "+"], ["+", "+", "+"]),
+            Border::Rounded => ("─", "│", ["╭", "┬", "╮"], ["├", "┼", "┤"], ["╰", "┴", "╯"]),
+            Border::Heavy => ("━", "┃", ["┏", "┳", "┓"], ["┣", "╋", "┫"], ["┗", "┻", "┛"]),
+            Border::Double => ("═", "║", ["╔", "╦", "╗"], ["╠", "╬", "╣"], ["╚", "╩", "╝"]),
+            Border::None => {
+                return None;
+            }
+        };
+        Some(BorderChars { horizontal, vertical, top, middle, bottom })
+    }
+}
+
+/// A struct representing a cell of a table.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ styled, table::{ Align, Cell }, Style };
+/// let plain = Cell::new("42").align(Align::Right);
+/// let styled_cell = Cell::from(styled("failed").red());
+/// let with_style = Cell::new("warning").style(Style::new().yellow());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Cell {
+    spans: Vec<Span>,
+    style: Style,
+    align: Option<Align>,
+}
+
+impl Cell {
+    /// Returns a cell with the text, which can already be painted.
+    ///
+    /// Lines in the text are kept as lines of the cell.
+    pub fn new(text: &str) -> Self {
+        Cell { spans: parse(text), style: Style::new(), align: None }
+    }
+
+    /// Returns the cell with a style for the whole cell.
+    ///
+    /// The styles of the text are painted on top of it and the padding gets it too,
+    /// so a background color fills the cell.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Returns the cell with an alignment other than the one of its column.
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = Some(align);
+        self
+    }
+
+    /// Returns the number of columns of the widest line of the cell.
+    fn width(&self) -> usize {
+        let text: String = self.spans
+            .iter()
+            .map(|s| s.text.as_str())
+            .collect();
+        text.lines().map(visible_width).max().unwrap_or(0)
+    }
+
+    /// Returns the number of columns of the widest grapheme of the cell.
+    fn widest_grapheme(&self) -> usize {
+        self.spans
+            .iter()
+            .flat_map(|s| s.text.graphemes(true))
+            .map(visible_width)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Cell::new(text)
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Cell::new(&text)
+    }
+}
+
+impl From<&StyledText> for Cell {
+    /// Returns a cell with the text painted the way the `StyledText` paints it.
+    fn from(styled: &StyledText) -> Self {
+        Cell::new(&styled.to_string())
+    }
+}
+
+impl From<&mut StyledText> for Cell {
+    fn from(styled: &mut StyledText) -> Self {
+        Cell::from(&*styled)
+    }
+}
+
+/// A struct holding the settings of a column.
+#[derive(Debug, Clone, Copy, Default)]
+struct Column {
+    align: Align,
+    width: Option<usize>,
+    max_width: Option<usize>,
+    overflow: Overflow,
+}
+
+/// A struct representing a table.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ styled, table::{ Align, Border, Cell, Table } };
+/// let mut table = Table::new();
+/// table
+///     .border(Border::Ascii)
+///     .align(1, Align::Right)
+///     .header(["name", "size"])
+///     .row(["Cargo.toml", "512"])
+///     .row([Cell::from(styled("src").blue()), Cell::new("4096")]);
+/// assert_eq!(
+///     table.paint(),
+///     "+------------+------+\n\
+///      | name       | size |\n\
+///      +------------+------+\n\
+///      | Cargo.toml |  512 |\n\
+///      | \x1b[34msrc\x1b[0m        | 4096 |\n\
+///      +------------+------+"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Table {
+    header: Option<Vec<Cell>>,
+    rows: Vec<Vec<Cell>>,
+    columns: Vec<Column>,
+    border: Border,
+    border_style: Style,
+    header_style: Style,
+    zebra: Option<Style>,
+    padding: usize,
+    color_level: ColorLevel,
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Table {
+            header: None,
+            rows: vec![],
+            columns: vec![],
+            border: Border::default(),
+            border_style: Style::new(),
+            header_style: Style::new(),
+            zebra: None,
+            padding: 1,
+            color_level: ColorLevel::default(),
+        }
+    }
+}
+
+impl Table {
+    /// Returns an empty table with rounded borders.
+    pub fn new() -> Self {
+        Table::default()
+    }
+
+    /// Sets the header row, it is separated from the other rows by a line.
+    pub fn header<I, C>(&mut self, cells: I) -> &mut Self where I: IntoIterator<Item = C>, C: Into<Cell> {
+        self.header = Some(cells.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Adds a row, rows with fewer cells than the others are filled with empty cells.
+    pub fn row<I, C>(&mut self, cells: I) -> &mut Self where I: IntoIterator<Item = C>, C: Into<Cell> {
+        self.rows.push(cells.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets the alignment of a column, columns start at 0.
+    pub fn align(&mut self, column: usize, align: Align) -> &mut Self {
+        self.column(column).align = align;
+        self
+    }
+
+    /// Sets the width of a column, the text is wrapped or truncated to it.
+    ///
+    /// **without a width, a column is as wide as its widest cell**
+    ///
+    /// **a column is never narrower than the widest character in it, like a wide CJK character**
+    pub fn width(&mut self, column: usize, width: usize) -> &mut Self {
+        self.column(column).width = Some(width.max(1));
+        self
+    }
+
+    /// Sets the most columns a column can take, narrower text keeps its width.
+    pub fn max_width(&mut self, column: usize, width: usize) -> &mut Self {
+        self.column(column).max_width = Some(width.max(1));
+        self
+    }
+
+    /// Sets what happens to text that is wider than its column.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::table::{ Border, Overflow, Table };
+    /// let mut table = Table::new();
+    /// table
+    ///     .border(Border::None)
+    ///     .max_width(0, 8)
+    ///     .overflow(0, Overflow::Truncate)
+    ///     .row(["a very long name", "1"]);
+    /// assert_eq!(table.paint(), " a very…   1 ");
+    /// ```
+    /// **the default is `Overflow::Wrap`**
+    pub fn overflow(&mut self, column: usize, overflow: Overflow) -> &mut Self {
+        self.column(column).overflow = overflow;
+        self
+    }
+
+    /// Sets the characters the borders are drawn with.
+    pub fn border(&mut self, border: Border) -> &mut Self {
+        self.border = border;
+        self
+    }
+
+    /// Sets the style of the borders.
+    pub fn border_style(&mut self, style: Style) -> &mut Self {
+        self.border_style = style;
+        self
+    }
+
+    /// Sets the style of the header cells.
+    pub fn header_style(&mut self, style: Style) -> &mut Self {
+        self.header_style = style;
+        self
+    }
+
+    /// Paints every second row with the style, starting at the second one.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::{ table::{ Border, Table }, Style };
+    /// let mut table = Table::new();
+    /// table.border(Border::None).zebra(Style::new().gray().bg()).row(["a"]).row(["b"]);
+    /// assert_eq!(table.paint(), " a \n\x1b[100m b \x1b[0m");
+    /// ```
+    pub fn zebra(&mut self, style: Style) -> &mut Self {
+        self.zebra = Some(style);
+        self
+    }
+
+    /// Sets the number of spaces on both sides of the text of a cell.
+    ///
+    /// **the default padding is 1**
+    pub fn padding(&mut self, padding: usize) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets the color level that `paint` should respect, see [`StyledText::color_level`].
+    ///
+    /// On `ColorLevel::NoColor` the escape codes of the cells are left out too.
+    pub fn color_level(&mut self, level: ColorLevel) -> &mut Self {
+        self.color_level = level;
+        self
+    }
+
+    /// Paints the table, the lines are separated by `\n` without one at the end.
+    pub fn paint(&self) -> String {
+        self.to_string()
+    }
+
+    /// Returns the settings of a column, adding columns up to it.
+    fn column(&mut self, column: usize) -> &mut Column {
+        if self.columns.len() <= column {
+            self.columns.resize(column + 1, Column::default());
+        }
+        &mut self.columns[column]
+    }
+
+    /// Returns the widths of the columns without padding.
+    fn widths(&self) -> Vec<usize> {
+        let count = self.header
+            .iter()
+            .chain(&self.rows)
+            .map(|r| r.len())
+            .max()
+            .unwrap_or(0);
+        (0..count)
+            .map(|i| {
+                let column = self.columns.get(i).copied().unwrap_or_default();
+                let cells = || self.header.iter().chain(&self.rows).filter_map(|r| r.get(i));
+                let natural = cells().map(Cell::width).max().unwrap_or(0);
+                let width = match (column.width, column.max_width) {
+                    (Some(width), _) => width,
+                    (None, Some(max)) => natural.min(max),
+                    (None, None) => natural,
+                };
+                // a grapheme can not be split, so the column has to fit the widest one
+                width.max(cells().map(Cell::widest_grapheme).max().unwrap_or(0))
+            })
+            .collect()
+    }
+
+    /// Returns the resolved style of a `Style` on the color level of the table.
+    fn span_style(&self, style: &Style) -> SpanStyle {
+        let mut span_style = SpanStyle::default();
+        for (s, p) in style.apply("").resolved_styles(self.color_level) {
+            span_style.apply(&s, &p);
+        }
+        span_style
+    }
+
+    /// Returns a border line like `├──┼──┤`, or `None` without borders.
+    fn rule(&self, widths: &[usize], pick: fn(&BorderChars) -> [&'static str; 3]) -> Option<Vec<Span>> {
+        let chars = self.border.chars()?;
+        let [left, middle, right] = pick(&chars);
+        let mut line = left.to_string();
+        for (i, width) in widths.iter().enumerate() {
+            if i > 0 {
+                line.push_str(middle);
+            }
+            line.push_str(&chars.horizontal.repeat(width + 2 * self.padding));
+        }
+        line.push_str(right);
+        Some(vec![segment(&line, self.span_style(&self.border_style), None)])
+    }
+
+    /// Returns the lines of a row with the style of the row under the styles of the cells.
+    fn row_lines(&self, cells: &[Cell], widths: &[usize], row_style: &SpanStyle) -> Vec<Vec<Span>> {
+        let border_style = self.span_style(&self.border_style);
+        let vertical = self.border.chars().map(|c| c.vertical);
+        let mut cell_lines = vec![];
+        let mut bases = vec![];
+        let empty = Cell::default();
+        for (i, width) in widths.iter().enumerate() {
+            let cell = cells.get(i).unwrap_or(&empty);
+            let column = self.columns.get(i).copied().unwrap_or_default();
+            let base = layer(row_style, &self.span_style(&cell.style), self.color_level);
+            cell_lines.push(lay_out(cell, *width, column.overflow));
+            bases.push((base, cell.align.unwrap_or(column.align)));
+        }
+        let height = cell_lines.iter().map(Vec::len).max().unwrap_or(1);
+
+        (0..height)
+            .map(|k| {
+                let mut line = vec![];
+                if let Some(v) = vertical {
+                    line.push(segment(v, border_style, None));
+                }
+                for (i, width) in widths.iter().enumerate() {
+                    if i > 0 {
+                        line.push(segment(vertical.unwrap_or(""), border_style, None));
+                    }
+                    let (base, align) = bases[i];
+                    let graphemes = cell_lines[i].get(k).map_or(&[][..], |l| l.as_slice());
+                    let free = width.saturating_sub(graphemes.iter().map(|g| visible_width(&g.text)).sum());
+                    let (before, after) = match align {
+                        Align::Left => (0, free),
+                        Align::Center => (free / 2, free - free / 2),
+                        Align::Right => (free, 0),
+                    };
+                    line.push(segment(&" ".repeat(self.padding + before), base, None));
+                    line.extend(graphemes.iter().map(|g| segment(&g.text, layer(&base, &g.style, self.color_level), g.link.as_deref())));
+                    line.push(segment(&" ".repeat(self.padding + after), base, None));
+                }
+                if let Some(v) = vertical {
+                    line.push(segment(v, border_style, None));
+                }
+                line.retain(|s| !s.text.is_empty());
+                line
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Table {
+    /// Writes the lines of the table with the fewest escape codes needed,
+    /// each line ends with the styles turned off.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let widths = self.widths();
+        if widths.is_empty() {
+            return Ok(());
+        }
+        let mut lines = vec![];
+        lines.extend(self.rule(&widths, |c| c.top));
+        if let Some(header) = &self.header {
+            lines.extend(self.row_lines(header, &widths, &self.span_style(&self.header_style)));
+            if !self.rows.is_empty() {
+                lines.extend(self.rule(&widths, |c| c.middle));
+            }
+        }
+        let plain = SpanStyle::default();
+        let zebra = self.zebra.map(|z| self.span_style(&z));
+        for (i, row) in self.rows.iter().enumerate() {
+            let row_style = match &zebra {
+                Some(z) if i % 2 == 1 => z,
+                _ => &plain,
+            };
+            lines.extend(self.row_lines(row, &widths, row_style));
+        }
+        lines.extend(self.rule(&widths, |c| c.bottom));
+
+        for (i, line) in lines.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            if self.color_level == ColorLevel::NoColor {
+                for span in line {
+                    f.write_str(&span.text)?;
+                }
+            } else {
+                f.write_str(&paint_spans(line))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns a span of text.
+fn segment(text: &str, style: SpanStyle, link: Option<&str>) -> Span {
+    Span { text: text.to_string(), style, link: link.map(str::to_string) }
+}
+
+/// Returns the style `top` painted over the style `base`.
+///
+/// The colors of `top` replace the ones of `base` and its formatters are added.
+/// The parts of `top` are downsampled to the color level first, like the styles of a `StyledText`,
+/// so colors in painted cell text follow the color level of the table too.
+fn layer(base: &SpanStyle, top: &SpanStyle, level: ColorLevel) -> SpanStyle {
+    let mut style = *base;
+    let mut apply = |s: Styles, paint_type: PaintType| {
+        if let Some(s) = s.downsample_as(level, &paint_type) {
+            style.apply(&s, &paint_type);
+        }
+    };
+    for f in top.formatters.iter() {
+        apply(Styles::StyleFormatter(f), PaintType::FG);
+    }
+    if let Some(u) = top.underline_style {
+        apply(Styles::StyleUnderline(u), PaintType::FG);
+    }
+    for (color, paint_type) in [(top.fg, PaintType::FG), (top.bg, PaintType::BG), (top.ul, PaintType::UL)] {
+        if let Some(c) = color {
+            apply(c.into(), paint_type);
+        }
+    }
+    style
+}
+
+/// Returns the lines of a cell as graphemes with their style, fitted into the width.
+fn lay_out(cell: &Cell, width: usize, overflow: Overflow) -> Vec<Vec<Span>> {
+    let mut lines = vec![vec![]];
+    for span in &cell.spans {
+        for grapheme in span.text.graphemes(true) {
+            if grapheme == "\n" || grapheme == "\r\n" {
+                lines.push(vec![]);
+            } else {
+                lines.last_mut().unwrap().push(segment(grapheme, span.style, span.link.as_deref()));
+            }
+        }
+    }
+    match overflow {
+        Overflow::Wrap => lines.into_iter().flat_map(|l| wrap(l, width)).collect(),
+        Overflow::Truncate => lines.into_iter().map(|l| truncate(l, width)).collect(),
+    }
+}
+
+/// Wraps a line of graphemes at the last space that fits into the width,
+/// a word without such a space is split.
+fn wrap(graphemes: Vec<Span>, width: usize) -> Vec<Vec<Span>> {
+    let mut lines = vec![];
+    let mut line: Vec<Span> = vec![];
+    let mut line_width = 0;
+    let mut last_space = None;
+    for g in graphemes {
+        let g_width = visible_width(&g.text);
+        let is_space = g.text.trim().is_empty();
+        if line_width + g_width > width && !line.is_empty() {
+            if is_space {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+                last_space = None;
+                continue;
+            }
+            let rest = match last_space.take() {
+                Some(i) => {
+                    let rest = line.split_off(i + 1);
+                    line.pop();
+                    rest
+                }
+                None => vec![],
+            };
+            lines.push(std::mem::replace(&mut line, rest));
+            line_width = line.iter().map(|g| visible_width(&g.text)).sum();
+        }
+        if is_space {
+            last_space = Some(line.len());
+        }
+        line.push(g);
+        line_width += g_width;
+    }
+    lines.push(line);
+    lines
+}
+
+/// Cuts a line of graphemes that is wider than the width and ends it with `…`.
+fn truncate(mut graphemes: Vec<Span>, width: usize) -> Vec<Span> {
+    if graphemes.iter().map(|g| visible_width(&g.text)).sum::<usize>() <= width {
+        return graphemes;
+    }
+    let mut kept: usize = 0;
+    let mut line_width = 0;
+    for g in &graphemes {
+        let g_width = visible_width(&g.text);
+        if line_width + g_width > width - 1 {
+            break;
+        }
+        line_width += g_width;
+        kept += 1;
+    }
+    let ellipsis = graphemes[kept.saturating_sub(1)].clone();
+    graphemes.truncate(kept);
+    while graphemes.last().is_some_and(|g| g.text.trim().is_empty()) {
+        graphemes.pop();
+    }
+    graphemes.push(Span { text: "…".to_string(), ..ellipsis });
+    graphemes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ strip_ansi, styled };
+
+    fn table(border: Border) -> Table {
+        let mut table = Table::new();
+        table.border(border).header(["a", "b"]).row(["1", "2"]);
+        table
+    }
+
+    #[test]
+    fn border_sets() {
+        assert_eq!(table(Border::Rounded).paint(), "╭───┬───╮\n│ a │ b │\n├───┼───┤\n│ 1 │ 2 │\n╰───┴───╯");
+        assert_eq!(table(Border::Heavy).paint(), "┏━━━┳━━━┓\n┃ a ┃ b ┃\n┣━━━╋━━━┫\n┃ 1 ┃ 2 ┃\n┗━━━┻━━━┛");
+        assert_eq!(table(Border::Double).paint(), "╔═══╦═══╗\n║ a ║ b ║\n╠═══╬═══╣\n║ 1 ║ 2 ║\n╚═══╩═══╝");
+        assert_eq!(table(Border::None).paint(), " a  b \n 1  2 ");
+        assert_eq!(Table::new().paint(), "");
+    }
+
+    #[test]
+    fn widths_ignore_escape_codes() {
+        let mut table = Table::new();
+        table.border(Border::Ascii).row([styled("你好").red().bold().into(), Cell::new("\x1b[4mx\x1b[0m")]).row(["abc", "yz"]);
+        let plain = strip_ansi(&table.paint());
+        assert_eq!(plain, "+------+----+\n| 你好 | x  |\n| abc  | yz |\n+------+----+");
+        assert!(plain.lines().all(|l| visible_width(l) == 13));
+    }
+
+    #[test]
+    fn alignment() {
+        let mut table = Table::new();
+        table
+            .border(Border::None)
+            .padding(0)
+            .align(0, Align::Right)
+            .align(1, Align::Center)
+            .row(["1", "x", "left"])
+            .row([Cell::new("100"), Cell::new("wide"), Cell::new("r").align(Align::Right)]);
+        assert_eq!(table.paint(), "  1 x  left\n100wide   r");
+    }
+
+    #[test]
+    fn wrap_keeps_styles() {
+        let mut table = Table::new();
+        table.border(Border::Ascii).width(0, 5).row([styled("one two three").red()]);
+        assert_eq!(
+            table.paint(),
+            "+-------+\n| \x1b[31mone\x1b[0m   |\n| \x1b[31mtwo\x1b[0m   |\n| \x1b[31mthree\x1b[0m |\n+-------+"
+        );
+    }
+
+    #[test]
+    fn wrap_splits_long_words() {
+        let lines = lay_out(&Cell::new("abcdefg hi"), 3, Overflow::Wrap);
+        let texts: Vec<String> = lines
+            .iter()
+            .map(|l| l.iter().map(|g| g.text.as_str()).collect())
+            .collect();
+        assert_eq!(texts, vec!["abc", "def", "g", "hi"]);
+    }
+
+    #[test]
+    fn columns_fit_wide_characters() {
+        let mut table = Table::new();
+        table.border(Border::Ascii).width(0, 1).row(["日本"]);
+        assert_eq!(table.paint(), "+----+\n| 日 |\n| 本 |\n+----+");
+        table.overflow(0, Overflow::Truncate);
+        assert_eq!(table.paint(), "+----+\n| …  |\n+----+");
+    }
+
+    #[test]
+    fn cell_lines_and_truncation() {
+        let mut table = Table::new();
+        table
+            .border(Border::None)
+            .padding(0)
+            .max_width(0, 4)
+            .overflow(0, Overflow::Truncate)
+            .row(["\x1b[1mabcdef\x1b[0m\nab", "x"]);
+        assert_eq!(table.paint(), "\x1b[1mabc…\x1b[0mx\nab   ");
+    }
+
+    #[test]
+    fn header_zebra_and_border_styles() {
+        let mut table = Table::new();
+        table
+            .border(Border::Ascii)
+            .padding(0)
+            .border_style(Style::new().gray())
+            .header_style(Style::new().bold())
+            .zebra(Style::new().blue().bg())
+            .header(["h"])
+            .row(["a"])
+            .row([styled("b").red()]);
+        assert_eq!(
+            table.paint(),
+            "\x1b[90m+-+\x1b[0m\n\
+             \x1b[90m|\x1b[0;1mh\x1b[0;90m|\x1b[0m\n\
+             \x1b[90m+-+\x1b[0m\n\
+             \x1b[90m|\x1b[0ma\x1b[90m|\x1b[0m\n\
+             \x1b[90m|\x1b[31;44mb\x1b[0;90m|\x1b[0m\n\
+             \x1b[90m+-+\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn cell_style_fills_padding() {
+        let mut table = Table::new();
+        table.border(Border::None).row([Cell::new("a").style(Style::new().green().bg()), Cell::new("b")]);
+        assert_eq!(table.paint(), "\x1b[42m a \x1b[0m b ");
+    }
+
+    #[test]
+    fn no_color_leaves_out_escape_codes() {
+        let mut table = Table::new();
+        table.border(Border::None).zebra(Style::new().red()).color_level(ColorLevel::NoColor).row(["x"]).row([
+            "\x1b[1my\x1b[0m",
+        ]);
+        assert_eq!(table.paint(), " x \n y ");
+    }
+
+    #[test]
+    fn cell_colors_follow_color_level() {
+        let mut table = Table::new();
+        table.border(Border::None).color_level(ColorLevel::Ansi16).row([Cell::from(styled("x").rgb(255, 0, 0))]);
+        assert_eq!(table.paint(), " \x1b[91mx\x1b[0m ");
+        table.color_level(ColorLevel::Ansi256).row(["\x1b[48;2;0;0;255my\x1b[0m"]);
+        assert_eq!(table.paint(), " \x1b[38;5;196mx\x1b[0m \n \x1b[48;5;21my\x1b[0m ");
+    }
+
+    #[test]
+    fn links_stay_inside_cells() {
+        let mut table = Table::new();
+        table.border(Border::Ascii).padding(0).row([styled("doc").link("http://x")]);
+        assert_eq!(
+            table.paint(),
+            "+---+\n|\x1b]8;;http://x\x1b\\doc\x1b]8;;\x1b\\|\n+---+"
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! A module with helpers for testing painted output.
+//!
+//! This module is only compiled with the `test-support` feature. It provides `readable`,
+//! which turns escape codes into tags like `<fg=#ff0000 bold>text</>`, `normalize`,
+//! which writes equivalent escape codes the same way, and the macros `assert_painted_eq!`
+//! and `assert_readable_eq!`, which panic with a colored diff of the readable forms.
+
+// =======================================================================
+
+use crate::{
+    ansi_code::parser::{ paint_spans, parse },
+    detect::stderr_color_level,
+    styled,
+    styles::{ color::Color, name_of, span_style::SpanStyle, Styles },
+};
+
+// =======================================================================
+
+/// Returns the painted string with its escape codes written as readable tags.
+///
+/// Text without style or link is written as it is, other text is put between `<...>` and `</>`.
+/// The tag lists the foreground, background and underline color as `fg=`, `bg=` and `ul=`,
+/// then the formatters and the underline style, and the url of a hyperlink as `link=`.
+/// Basic colors are written with their name, palette colors as `color(n)` and rgb colors in hex.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ styled, testing::readable };
+/// let painted = styled("Error:").rgb(255, 0, 0).bold().paint() + " not found";
+/// assert_eq!(readable(&painted), "<fg=#ff0000 bold>Error:</> not found");
+/// ```
+pub fn readable(painted: &str) -> String {
+    let mut output = String::new();
+    for span in parse(painted) {
+        let mut tokens = style_tokens(&span.style);
+        if let Some(url) = &span.link {
+            tokens.push(format!("link={url}"));
+        }
+        if tokens.is_empty() {
+            output.push_str(&span.text);
+        } else {
+            output.push_str(&format!("<{}>{}</>", tokens.join(" "), span.text));
+        }
+    }
+    output
+}
+
+/// Returns the painted string written with the fewest escape codes in a fixed order.
+///
+/// Strings that look the same on a terminal are normalized to the same string,
+/// whatever the order of the parameters, how they are split over sequences or
+/// which codes have no effect. Escape sequences that are not SGR or OSC 8 are removed.
+///
+/// # Example:
+/// ```
+/// use term_tools::testing::normalize;
+/// assert_eq!(normalize("\x1b[31m\x1b[1mhi\x1b[22;39m"), normalize("\x1b[1;31mhi\x1b[0m"));
+/// ```
+pub fn normalize(painted: &str) -> String {
+    paint_spans(&parse(painted))
+}
+
+/// Returns a line diff of the two strings, lines only in `left` start with `-` and are red,
+/// lines only in `right` start with `+` and are green.
+///
+/// The colors follow the color level of stderr, where panic messages are written.
+pub fn diff(left: &str, right: &str) -> String {
+    let left: Vec<&str> = left.split('\n').collect();
+    let right: Vec<&str> = right.split('\n').collect();
+
+    // The lengths of the longest common subsequences of the remaining lines.
+    let mut lengths = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            lengths[i][j] = if left[i] == right[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let level = stderr_color_level();
+    let mut lines = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() || j < right.len() {
+        if i < left.len() && j < right.len() && left[i] == right[j] {
+            lines.push(format!("  {}", left[i]));
+            i += 1;
+            j += 1;
+        } else if j == right.len() || (i < left.len() && lengths[i + 1][j] >= lengths[i][j + 1]) {
+            lines.push(styled(&format!("- {}", left[i])).red().color_level(level).paint());
+            i += 1;
+        } else {
+            lines.push(styled(&format!("+ {}", right[j])).green().color_level(level).paint());
+            j += 1;
+        }
+    }
+    lines.join("\n")
+}
+
+/// Returns `Ok` if the painted strings look the same on a terminal,
+/// otherwise a message with the diff of their readable forms.
+///
+/// This is the check of `assert_painted_eq!`.
+pub fn check_painted_eq<L, R>(left: &L, right: &R) -> Result<(), String>
+    where L: AsRef<str> + ?Sized, R: AsRef<str> + ?Sized
+{
+    let (left, right) = (left.as_ref(), right.as_ref());
+    if normalize(left) == normalize(right) {
+        return Ok(());
+    }
+    let (left, right) = (readable(left), readable(right));
+    Err(format!("painted strings are not equal (-left +right):\n{}", diff(&left, &right)))
+}
+
+/// Returns `Ok` if the readable form of the painted string is `expected`,
+/// otherwise a message with the diff.
+///
+/// This is the check of `assert_readable_eq!`.
+pub fn check_readable_eq<P, E>(painted: &P, expected: &E) -> Result<(), String>
+    where P: AsRef<str> + ?Sized, E: AsRef<str> + ?Sized
+{
+    let actual = readable(painted.as_ref());
+    if actual == expected.as_ref() {
+        return Ok(());
+    }
+    Err(format!("readable form is not as expected (-actual +expected):\n{}", diff(&actual, expected.as_ref())))
+}
+
+/// Asserts that two painted strings look the same on a terminal.
+///
+/// The strings are compared after `normalize`, so the order of the parameters does not matter.
+/// On failure it panics with a colored diff of their `readable` forms.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ assert_painted_eq, styled };
+/// assert_painted_eq!(styled("ok").green().bold().paint(), "\x1b[32m\x1b[1mok\x1b[0m");
+/// ```
+#[macro_export]
+macro_rules! assert_painted_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        if let Err(message) = $crate::testing::check_painted_eq(&$left, &$right) {
+            panic!("{}", message);
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        if let Err(message) = $crate::testing::check_painted_eq(&$left, &$right) {
+            panic!("{}: {}", format_args!($($arg)+), message);
+        }
+    };
+}
+
+/// Asserts that the `readable` form of a painted string is the expected string.
+///
+/// On failure it panics with a colored diff.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ assert_readable_eq, styled };
+/// assert_readable_eq!(styled("warn").yellow().bg().paint(), "<bg=yellow>warn</>");
+/// ```
+#[macro_export]
+macro_rules! assert_readable_eq {
+    ($painted:expr, $expected:expr $(,)?) => {
+        if let Err(message) = $crate::testing::check_readable_eq(&$painted, &$expected) {
+            panic!("{}", message);
+        }
+    };
+    ($painted:expr, $expected:expr, $($arg:tt)+) => {
+        if let Err(message) = $crate::testing::check_readable_eq(&$painted, &$expected) {
+            panic!("{}: {}", format_args!($($arg)+), message);
+        }
+    };
+}
+
+/// Returns the tokens of a style in the order of `readable`.
+fn style_tokens(style: &SpanStyle) -> Vec<String> {
+    let mut tokens = vec![];
+    for (name, color) in [("fg", &style.fg), ("bg", &style.bg), ("ul", &style.ul)] {
+        if let Some(color) = color {
+            tokens.push(format!("{name}={}", color_name(color)));
+        }
+    }
+    for f in style.formatters.iter() {
+        tokens.push(match f.name() {
+            Some(name) => name.to_string(),
+            None => format!("sgr({})", f.code),
+        });
+    }
+    if let Some(name) = style.underline_style.and_then(|u| name_of(&Styles::StyleUnderline(u))) {
+        tokens.push(name.to_string());
+    }
+    tokens
+}
+
+/// Returns a color the way `readable` writes it.
+fn color_name(color: &Color) -> String {
+    match color {
+        Color::Basic(c) => c.name().map_or_else(|| format!("sgr({})", c.fg), |n| n.to_string()),
+        Color::Palette(c) => format!("color({})", c.index),
+        Color::Rgb(c) => c.to_hex(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readable_tags() {
+        assert_eq!(readable("plain"), "plain");
+        assert_eq!(
+            readable("\x1b[1;3;38;5;208;48;2;0;0;255mx\x1b[0m y"),
+            "<fg=color(208) bg=#0000ff bold italic>x</> y"
+        );
+        assert_eq!(readable("\x1b[4:3;58;5;1mwavy\x1b[0m"), "<ul=color(1) curly_underline>wavy</>");
+        assert_eq!(readable("\x1b]8;;https://x.y\x1b\\\x1b[92mlink\x1b[0m\x1b]8;;\x1b\\"), "<fg=bright_green link=https://x.y>link</>");
+    }
+
+    #[test]
+    fn normalize_equivalent_codes() {
+        let expected = "\x1b[1;31mhi\x1b[0m";
+        assert_eq!(normalize("\x1b[31;1mhi\x1b[m"), expected);
+        assert_eq!(normalize("\x1b[31m\x1b[1m\x1b[mhi"), "hi");
+        assert_eq!(normalize("\x1b[1m\x1b[31mhi\x1b[22;39m"), expected);
+        assert_eq!(normalize("\x1b[1;31mhi"), expected);
+        assert_eq!(normalize("\x1b]8;;http://a\x07a\x1b]8;;\x07b"), "\x1b]8;;http://a\x1b\\a\x1b]8;;\x1b\\b");
+    }
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        let diff = diff("a\nb\nc", "a\nx\nc");
+        let lines: Vec<String> = diff.lines().map(crate::strip_ansi).collect();
+        assert_eq!(lines, vec!["  a", "- b", "+ x", "  c"]);
+    }
+
+    #[test]
+    fn checks_return_messages() {
+        assert_eq!(check_painted_eq("\x1b[32;1mok\x1b[0m", "\x1b[1m\x1b[32mok\x1b[0m"), Ok(()));
+        let message = check_painted_eq("\x1b[31mok\x1b[0m", "ok").unwrap_err();
+        assert!(crate::strip_ansi(&message).contains("- <fg=red>ok</>\n+ ok"));
+        assert_eq!(check_readable_eq(&"\x1b[2mx\x1b[0m".to_string(), "<faint>x</>"), Ok(()));
+        assert!(check_readable_eq("x", "<bold>x</>").is_err());
+    }
+
+    #[test]
+    fn assertion_macros() {
+        assert_painted_eq!(styled("a").bold().red().paint(), "\x1b[31;1ma\x1b[0m");
+        assert_readable_eq!(styled("a").italic().paint(), "<italic>a</>", "italic text");
+    }
+
+    #[test]
+    #[should_panic(expected = "painted strings are not equal")]
+    fn assert_painted_eq_panics() {
+        assert_painted_eq!("\x1b[1ma\x1b[0m", "a");
+    }
+}
@@ -0,0 +1,333 @@
+//! A module for styles that are looked up by their meaning.
+//!
+//! This module provides a struct `Theme` that maps semantic roles like `error` or `muted`
+//! to reusable `Style`s, with a built-in dark and light theme. One theme is the current theme
+//! of the program, it can be switched at any time with `set_theme` and used with `paint`.
+//!
+//! With the `toml` or `json` feature, themes can be loaded from files where every role
+//! is a style string like `"bold #dc322f"`, as `Style` parses it with `FromStr`.
+
+// =======================================================================
+
+use std::{ collections::BTreeMap, error::Error, fmt, sync::RwLock };
+
+use crate::{ style::{ ParseStyleError, Style }, StyledText };
+
+// =======================================================================
+
+/// The roles that the built-in themes define.
+pub const ROLES: [&str; 6] = ["error", "warning", "success", "info", "muted", "accent"];
+
+/// The current theme, `None` is the dark theme.
+static CURRENT: RwLock<Option<Theme>> = RwLock::new(None);
+
+/// A struct representing a set of styles named by their role.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ theme::Theme, Style };
+/// let mut theme = Theme::new("mine");
+/// theme.set("error", Style::new().red().bold());
+/// assert_eq!(theme.paint("error", "failed"), "\x1b[1m\x1b[31mfailed\x1b[0m");
+/// assert_eq!(theme.paint("unknown", "plain"), "plain");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Theme {
+    name: String,
+    roles: BTreeMap<String, Style>,
+}
+
+impl Theme {
+    /// Returns a theme without any role.
+    pub fn new(name: &str) -> Self {
+        Theme { name: name.to_string(), roles: BTreeMap::new() }
+    }
+
+    /// Returns the built-in theme for terminals with a dark background.
+    pub fn dark() -> Self {
+        let mut theme = Theme::new("dark");
+        theme
+            .set("error", Style::new().bright_red().bold())
+            .set("warning", Style::new().bright_yellow())
+            .set("success", Style::new().bright_green())
+            .set("info", Style::new().bright_cyan())
+            .set("muted", Style::new().gray())
+            .set("accent", Style::new().bright_magenta().bold());
+        theme
+    }
+
+    /// Returns the built-in theme for terminals with a light background.
+    pub fn light() -> Self {
+        let mut theme = Theme::new("light");
+        theme
+            .set("error", Style::new().red().bold())
+            .set("warning", Style::new().palette(130))
+            .set("success", Style::new().green())
+            .set("info", Style::new().blue())
+            .set("muted", Style::new().palette(244))
+            .set("accent", Style::new().magenta().bold());
+        theme
+    }
+
+    /// Returns the name of the theme.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Sets the style of a role, a style that is already set is replaced.
+    pub fn set(&mut self, role: &str, style: Style) -> &mut Self {
+        self.roles.insert(role.to_string(), style);
+        self
+    }
+
+    /// Returns the style of a role, or `None` if the theme does not define it.
+    pub fn get(&self, role: &str) -> Option<Style> {
+        self.roles.get(role).copied()
+    }
+
+    /// Returns the roles of the theme with their styles, ordered by the name of the role.
+    pub fn roles(&self) -> impl Iterator<Item = (&str, &Style)> {
+        self.roles.iter().map(|(role, style)| (role.as_str(), style))
+    }
+
+    /// Returns a `StyledText` of the text with the style of the role.
+    ///
+    /// If the theme does not define the role, the text has no style.
+    pub fn apply(&self, role: &str, text: &str) -> StyledText {
+        self.get(role).unwrap_or_default().apply(text)
+    }
+
+    /// Paints the text with the style of the role.
+    ///
+    /// If the theme does not define the role, the text is returned as it is.
+    pub fn paint(&self, role: &str, text: &str) -> String {
+        match self.get(role) {
+            Some(style) => style.paint(text),
+            None => text.to_string(),
+        }
+    }
+
+    /// Reads a theme from TOML, with the styles of the roles in a `roles` table.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::{ theme::Theme, Style };
+    /// let theme = Theme::from_toml(r##"
+    ///     name = "solarized"
+    ///     [roles]
+    ///     error = "bold #dc322f"
+    ///     muted = "color(244)"
+    /// "##)?;
+    /// assert_eq!(theme.name(), "solarized");
+    /// assert_eq!(theme.get("muted"), Some(Style::new().palette(244).fg()));
+    /// # Ok::<(), term_tools::theme::ThemeError>(())
+    /// ```
+    #[cfg(feature = "toml")]
+    pub fn from_toml(text: &str) -> Result<Self, ThemeError> {
+        let table: toml::Table = text.parse().map_err(|err: toml::de::Error| ThemeError::Syntax(err.to_string()))?;
+        let name = match table.get("name") {
+            Some(name) => Some(name.as_str().ok_or_else(|| ThemeError::InvalidField("name".to_string()))?),
+            None => None,
+        };
+        let roles = match table.get("roles") {
+            Some(roles) => roles.as_table().ok_or_else(|| ThemeError::InvalidField("roles".to_string()))?,
+            None => &toml::Table::new(),
+        };
+        Theme::from_entries(name, roles.iter().map(|(role, style)| (role.as_str(), style.as_str())))
+    }
+
+    /// Reads a theme from JSON, with the styles of the roles in a `roles` object.
+    ///
+    /// # Example:
+    /// ```
+    /// use term_tools::{ theme::Theme, Style };
+    /// let theme = Theme::from_json(r#"{ "name": "mine", "roles": { "error": "red on white" } }"#)?;
+    /// assert_eq!(theme.get("error"), Some(Style::new().red().fg().white().bg()));
+    /// # Ok::<(), term_tools::theme::ThemeError>(())
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn from_json(text: &str) -> Result<Self, ThemeError> {
+        let value: serde_json::Value = serde_json::from_str(text).map_err(|err| ThemeError::Syntax(err.to_string()))?;
+        let object = value.as_object().ok_or_else(|| ThemeError::Syntax("expected an object".to_string()))?;
+        let name = match object.get("name") {
+            Some(name) => Some(name.as_str().ok_or_else(|| ThemeError::InvalidField("name".to_string()))?),
+            None => None,
+        };
+        let roles = match object.get("roles") {
+            Some(roles) => roles.as_object().ok_or_else(|| ThemeError::InvalidField("roles".to_string()))?,
+            None => &serde_json::Map::new(),
+        };
+        Theme::from_entries(name, roles.iter().map(|(role, style)| (role.as_str(), style.as_str())))
+    }
+
+    /// Reads a theme from a file, the extension `toml` or `json` picks the format.
+    #[cfg(any(feature = "toml", feature = "json"))]
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self, ThemeError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|err| ThemeError::Io(err.to_string()))?;
+        match path.extension().and_then(|e| e.to_str()) {
+            #[cfg(feature = "toml")]
+            Some("toml") => Theme::from_toml(&text),
+            #[cfg(feature = "json")]
+            Some("json") => Theme::from_json(&text),
+            extension => Err(ThemeError::UnknownFormat(extension.unwrap_or_default().to_string())),
+        }
+    }
+
+    /// Returns a theme of the roles, every style must be a string that `Style` can be parsed from.
+    #[cfg(any(feature = "toml", feature = "json"))]
+    fn from_entries<'a>(
+        name: Option<&str>,
+        roles: impl Iterator<Item = (&'a str, Option<&'a str>)>
+    ) -> Result<Self, ThemeError> {
+        let mut theme = Theme::new(name.unwrap_or_default());
+        for (role, style) in roles {
+            let style = style.ok_or_else(|| ThemeError::InvalidField(format!("roles.{}", role)))?;
+            let style = style
+                .parse()
+                .map_err(|error| ThemeError::InvalidStyle { role: role.to_string(), error })?;
+            theme.set(role, style);
+        }
+        Ok(theme)
+    }
+}
+
+/// An enum representing why a theme could not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThemeError {
+    /// The file could not be read, with the message of the io error.
+    Io(String),
+    /// The file extension is not `toml` or `json`, or its feature is not enabled.
+    UnknownFormat(String),
+    /// The text is not valid TOML or JSON, with the message of the parser.
+    Syntax(String),
+    /// A field has the wrong type, like a style that is not a string.
+    InvalidField(String),
+    /// The style of a role has a word that is not a known style.
+    InvalidStyle { role: String, error: ParseStyleError },
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeError::Io(message) => write!(f, "cannot read the theme: {}", message),
+            ThemeError::UnknownFormat(extension) => {
+                write!(f, "`{}` is not a known theme format", extension)
+            }
+            ThemeError::Syntax(message) => write!(f, "invalid theme: {}", message),
+            ThemeError::InvalidField(field) => write!(f, "the field `{}` has the wrong type", field),
+            ThemeError::InvalidStyle { role, error } => write!(f, "the role `{}` has an {}", role, error),
+        }
+    }
+}
+
+impl Error for ThemeError {}
+
+/// Sets the current theme of the program.
+pub fn set_theme(theme: Theme) {
+    *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = Some(theme);
+}
+
+/// Returns a copy of the current theme, which is the dark theme until `set_theme` is called.
+pub fn current_theme() -> Theme {
+    CURRENT.read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(Theme::dark)
+}
+
+/// Paints the text with the style of the role in the current theme.
+///
+/// # Example:
+/// ```
+/// use term_tools::theme::{ self, Theme };
+/// theme::set_theme(Theme::light());
+/// assert_eq!(theme::paint("success", "done"), "\x1b[32mdone\x1b[0m");
+/// ```
+pub fn paint(role: &str, text: &str) -> String {
+    let current = CURRENT.read().unwrap_or_else(|e| e.into_inner());
+    match current.as_ref() {
+        Some(theme) => theme.paint(role, text),
+        None => Theme::dark().paint(role, text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_themes_define_all_roles() {
+        for theme in [Theme::dark(), Theme::light()] {
+            for role in ROLES {
+                assert!(theme.get(role).is_some(), "{} has no {}", theme.name(), role);
+            }
+        }
+    }
+
+    #[test]
+    fn paint_roles() {
+        let theme = Theme::dark();
+        assert_eq!(theme.paint("error", "a"), "\x1b[1m\x1b[91ma\x1b[0m");
+        assert_eq!(theme.paint("missing", "a"), "a");
+        assert_eq!(theme.apply("muted", "a").underline().paint(), "\x1b[90m\x1b[4ma\x1b[0m");
+    }
+
+    #[test]
+    fn replace_role() {
+        let mut theme = Theme::light();
+        theme.set("error", Style::new().inverse());
+        assert_eq!(theme.get("error"), Some(Style::new().inverse()));
+        assert_eq!(theme.roles().count(), ROLES.len());
+    }
+
+    #[test]
+    fn switch_current_theme() {
+        set_theme(Theme::light());
+        assert_eq!(current_theme().name(), "light");
+        assert_eq!(paint("info", "a"), "\x1b[34ma\x1b[0m");
+        let mut custom = Theme::new("custom");
+        custom.set("info", Style::new().bold());
+        set_theme(custom);
+        assert_eq!(paint("info", "a"), "\x1b[1ma\x1b[0m");
+        assert_eq!(paint("error", "a"), "a");
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn load_toml() {
+        let theme = Theme::from_toml("name = \"t\"\n[roles]\nerror = \"bold red\"\nmuted = \"dim\"").unwrap();
+        assert_eq!(theme.name(), "t");
+        assert_eq!(theme.get("error"), Some(Style::new().bold().red().fg()));
+        assert_eq!(theme.get("muted"), Some(Style::new().faint()));
+
+        assert_eq!(
+            Theme::from_toml("[roles]\nerror = \"bold rde\""),
+            Err(ThemeError::InvalidStyle {
+                role: "error".to_string(),
+                error: ParseStyleError { style: "rde".to_string() },
+            })
+        );
+        assert_eq!(Theme::from_toml("[roles]\nerror = 1"), Err(ThemeError::InvalidField("roles.error".to_string())));
+        assert!(matches!(Theme::from_toml("roles = ["), Err(ThemeError::Syntax(_))));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn load_json() {
+        let theme = Theme::from_json(r#"{ "roles": { "accent": "on color(17)" } }"#).unwrap();
+        assert_eq!(theme.name(), "");
+        assert_eq!(theme.get("accent"), Some(Style::new().palette(17).bg()));
+        assert_eq!(Theme::from_json(r#"{ "roles": [] }"#), Err(ThemeError::InvalidField("roles".to_string())));
+        assert!(matches!(Theme::from_json("{"), Err(ThemeError::Syntax(_))));
+    }
+
+    #[test]
+    fn error_messages() {
+        let err = ThemeError::InvalidStyle {
+            role: "error".to_string(),
+            error: ParseStyleError { style: "rde".to_string() },
+        };
+        assert_eq!(err.to_string(), "the role `error` has an unknown style `rde`");
+    }
+}
@@ -0,0 +1,106 @@
+//! A module for measuring painted strings.
+//!
+//! This module provides `strip_ansi`, which removes every escape sequence from a string,
+//! and `visible_width`, which returns how many terminal columns a painted string takes.
+//! The width is measured per grapheme cluster, so East Asian wide characters and emoji
+//! take two columns and combining marks take none.
+
+// =======================================================================
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::ansi_code::parser::read_escape;
+
+// =======================================================================
+
+/// Removes all escape sequences (CSI, OSC and the other control strings) from the string.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ strip_ansi, styled };
+/// let painted = styled("Stay hungry").red().bold().paint();
+/// assert_eq!(strip_ansi(&painted), "Stay hungry");
+/// ```
+pub fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            read_escape(&mut chars);
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
+
+/// Returns the number of terminal columns the string takes when printed.
+///
+/// Escape sequences take no columns, wide characters and emoji take two columns
+/// and zero width characters like combining marks take none.
+///
+/// # Example:
+/// ```
+/// use term_tools::{ styled, visible_width };
+/// let painted = styled("日本語 ok").green().paint();
+/// assert_eq!(visible_width(&painted), 9);
+/// ```
+pub fn visible_width(text: &str) -> usize {
+    if !text.contains('\x1b') {
+        return grapheme_width(text);
+    }
+    grapheme_width(&strip_ansi(text))
+}
+
+/// Returns the width of a string without escape sequences, measured per grapheme cluster.
+fn grapheme_width(text: &str) -> usize {
+    text.graphemes(true)
+        .map(|g| g.width().min(2))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_sgr_sequences() {
+        assert_eq!(strip_ansi("\x1b[1;31mred\x1b[0m plain"), "red plain");
+        assert_eq!(strip_ansi("no codes"), "no codes");
+    }
+
+    #[test]
+    fn strip_other_sequences() {
+        assert_eq!(strip_ansi("a\x1b[2Kb\x1b[?25hc"), "abc");
+        assert_eq!(strip_ansi("\x1b]8;;http://example.com\x1b\\link\x1b]8;;\x1b\\"), "link");
+        assert_eq!(strip_ansi("\x1b]0;title\x07text"), "text");
+        assert_eq!(strip_ansi("trailing\x1b"), "trailing");
+    }
+
+    #[test]
+    fn width_of_ascii() {
+        assert_eq!(visible_width("hello"), 5);
+        assert_eq!(visible_width("\x1b[31mhello\x1b[0m"), 5);
+        assert_eq!(visible_width(""), 0);
+    }
+
+    #[test]
+    fn width_of_wide_characters() {
+        assert_eq!(visible_width("日本語"), 6);
+        assert_eq!(visible_width("\x1b[1m한국어\x1b[0m!"), 7);
+    }
+
+    #[test]
+    fn width_of_zero_width_characters() {
+        assert_eq!(visible_width("e\u{301}"), 1);
+        assert_eq!(visible_width("a\u{200b}b"), 2);
+    }
+
+    #[test]
+    fn width_of_emoji() {
+        assert_eq!(visible_width("\u{1f600}"), 2);
+        assert_eq!(visible_width("\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}"), 2);
+        assert_eq!(visible_width("\u{1f44d}\u{1f3fd}"), 2);
+    }
+}